@@ -1,19 +1,31 @@
 use ears::*;
 use gfx_device_gl;
 use piston_window::*;
-use std;
+use std::rc::Rc;
+
+use crate::error::Error;
+use crate::vfs::Vfs;
 
 pub struct AssetLoader {
-    pub assets_path: std::path::PathBuf,
+    pub vfs: Rc<Vfs>,
     pub factory: gfx_device_gl::Factory,
 }
 
 impl AssetLoader {
-    pub fn load_texture(&self, relative_path: &str) -> G2dTexture {
-        Texture::from_path(&mut self.factory.clone(), self.assets_path.join(relative_path), Flip::None, &TextureSettings::new()).unwrap()
+    pub fn load_texture(&self, asset_path: &str) -> Result<G2dTexture, Error> {
+        let bytes = self.vfs.read(asset_path).map_err(|err| Error::missing_asset(asset_path, &err))?;
+        let image = ::image::load_from_memory(&bytes).map_err(|err| Error::bad_asset(asset_path, &err))?.to_rgba();
+        Texture::from_image(&mut self.factory.clone(), &image, &TextureSettings::new()).map_err(|err| Error::bad_asset(asset_path, &err))
+    }
+
+    // ears can only open sounds by file name.
+    pub fn load_sound(&self, asset_path: &str) -> Result<Sound, Error> {
+        let path = self.vfs.file_path(asset_path).map_err(|err| Error::missing_asset(asset_path, &err))?;
+        Sound::new(&path.to_string_lossy()).map_err(|err| Error::bad_asset(asset_path, &err))
     }
 
-    pub fn load_sound(&self, relative_path: &str) -> Sound {
-        Sound::new(self.assets_path.join(relative_path).to_str().unwrap()).unwrap()
+    pub fn load_font(&self, asset_path: &str) -> Result<conrod_core::text::Font, Error> {
+        let bytes = self.vfs.read(asset_path).map_err(|err| Error::missing_asset(asset_path, &err))?;
+        conrod_core::text::FontCollection::from_bytes(bytes).and_then(|font_collection| font_collection.into_font()).map_err(|err| Error::bad_asset(asset_path, &err))
     }
 }
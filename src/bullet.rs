@@ -1,5 +1,10 @@
+use crate::bullet_behavior::BulletBehavior;
 use crate::collidable::Collidable;
+use crate::collidable::CollidableHandle;
+use crate::collidable::Collision;
 use crate::collidable_object::CollidableObject;
+use crate::config::CollisionConfig;
+use crate::config::CollisionResponse;
 use crate::game_object::GameObject;
 use crate::input;
 use crate::object_type::ObjectType;
@@ -7,21 +12,34 @@ use crate::renderable::Renderable;
 use crate::renderable_object::RenderableObject;
 use crate::updatable::Updatable;
 use crate::vector2::Vector2;
-use crate::world::WorldReq;
+use crate::simulation::WorldReq;
+use crate::simulation::WorldRequestType;
 use piston_window::Key;
 use piston_window::MouseButton;
 use piston_window::UpdateArgs;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 pub struct Bullet {
     pub position: Vector2,
     pub rotation: f64,
+    pub previous_position: Vector2,
+    pub previous_rotation: f64,
     pub scale: f64,
     pub renderable_object: RenderableObject,
     pub velocity: Vector2,
     pub should_delete: bool,
     pub collidable_object: CollidableObject,
     pub is_visible: bool,
+    // `Bullet` for the player's, `EnemyBullet` for turrets'.
+    pub object_type: ObjectType,
+    // Shared with every other bullet from the same gun concept.
+    pub bullet_behavior: Rc<BulletBehavior>,
+    // How many times it has bounced off walls.
+    pub bounces: u32,
+    // What a piercing bullet has already gone through, so it only hits each thing once.
+    pub pierced: Vec<CollidableHandle>,
 }
 
 impl GameObject for Bullet {
@@ -45,8 +63,16 @@ impl GameObject for Bullet {
         self.should_delete = should_delete
     }
 
+    fn get_previous_position(&self) -> &Vector2 {
+        &self.previous_position
+    }
+
+    fn get_previous_rotation(&self) -> f64 {
+        self.previous_rotation
+    }
+
     fn get_object_type(&self) -> ObjectType {
-        ObjectType::Bullet
+        self.object_type
     }
 }
 
@@ -63,8 +89,11 @@ impl Renderable for Bullet {
 impl Updatable for Bullet {
     #[allow(unused_variables)]
     fn update(&mut self, key_states: &HashMap<Key, input::ButtonState>, mouse_states: &HashMap<MouseButton, input::ButtonState>, mouse_pos: &Vector2, args: UpdateArgs) -> Vec<WorldReq> {
+        self.previous_position = self.position;
+        self.previous_rotation = self.rotation;
         self.position += self.velocity * args.dt;
-        Vec::new()
+        let bullet_behavior = self.bullet_behavior.clone();
+        bullet_behavior.update(self, args.dt)
     }
 }
 
@@ -73,15 +102,56 @@ impl Collidable for Bullet {
         &self.collidable_object
     }
 
-    fn collide(&mut self, other_object_type: ObjectType) {
-        match other_object_type {
-            ObjectType::Wall => {
-                self.set_should_delete(true);
-            }
-            ObjectType::Enemy => {
-                self.set_should_delete(true);
-            }
-            _ => {}
-        }
+    fn get_velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    fn set_velocity(&mut self, velocity: Vector2) {
+        self.velocity = velocity
+    }
+
+    fn set_position(&mut self, position: Vector2) {
+        self.position = position
+    }
+
+    fn collide(&mut self, collision: &Collision) -> Vec<WorldReq> {
+        let bullet_behavior = self.bullet_behavior.clone();
+        bullet_behavior.collide(self, collision)
+    }
+
+    fn passes_through(&self, other_handle: CollidableHandle) -> bool {
+        self.pierced.contains(&other_handle)
+    }
+
+    fn collision_response(&self, other_object_type: ObjectType, collision_config: &CollisionConfig) -> CollisionResponse {
+        self.bullet_behavior.collision_response(self, other_object_type, collision_config)
+    }
+
+    fn kind_names(&self) -> Vec<String> {
+        vec![String::from(self.object_type.name()), String::from(self.bullet_behavior.name())]
+    }
+}
+
+impl Bullet {
+    // TODO: https://stackoverflow.com/questions/28632968/why-doesnt-rust-support-trait-object-upcasting
+    pub fn world_requests(bullet: Rc<RefCell<Bullet>>) -> Vec<WorldReq> {
+        vec![
+            WorldReq {
+                renderable: Some(bullet.clone()),
+                updatable: None,
+                collidable: Some(bullet.clone()),
+                sound: None,
+                explosion: None,
+                req_type: WorldRequestType::AddDynamicRenderable,
+            },
+            WorldReq {
+                renderable: None,
+                updatable: Some(bullet),
+                collidable: None,
+                sound: None,
+                explosion: None,
+                req_type: WorldRequestType::AddUpdatable,
+            },
+        ]
     }
 }
@@ -0,0 +1,36 @@
+use crate::bullet::Bullet;
+use crate::collidable::Collision;
+use crate::config::CollisionConfig;
+use crate::config::CollisionResponse;
+use crate::game_object::GameObject;
+use crate::object_type::ObjectType;
+use crate::simulation::WorldReq;
+
+// What makes one kind of bullet different from another. Every bullet a gun concept fires shares its behavior, so
+// anything that changes from bullet to bullet, like how often it has bounced, lives on the `Bullet`.
+pub trait BulletBehavior {
+    // Shown under the bullet in the HUD.
+    fn name(&self) -> &str;
+
+    fn speed_multiplier(&self) -> f64 {
+        1.0
+    }
+
+    fn scale_multiplier(&self) -> f64 {
+        1.0
+    }
+
+    fn collision_response(&self, bullet: &Bullet, other_object_type: ObjectType, collision_config: &CollisionConfig) -> CollisionResponse {
+        collision_config.response(bullet.get_object_type(), other_object_type)
+    }
+
+    // After the simulation has done whatever `collision.response` says to the bullet.
+    fn collide(&self, _bullet: &mut Bullet, _collision: &Collision) -> Vec<WorldReq> {
+        Vec::new()
+    }
+
+    // After the bullet has moved for the tick.
+    fn update(&self, _bullet: &mut Bullet, _dt: f64) -> Vec<WorldReq> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,27 @@
+use crate::enemy_behavior::EnemyBehavior;
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
+
+pub struct ChaseEnemyBehavior {
+    pub speed: f64,
+}
+
+impl EnemyBehavior for ChaseEnemyBehavior {
+    fn is_static(&self) -> bool {
+        false
+    }
+
+    // Straight at the player, walls or no walls; the collision table decides whether walls stop it.
+    fn velocity(&mut self, position: &Vector2, player_position: &Vector2, dt: f64) -> Vector2 {
+        let to_player = *player_position - *position;
+        let distance = to_player.magnitude();
+        if distance <= self.speed * dt {
+            return to_player / dt;
+        }
+        to_player.normalized() * self.speed
+    }
+
+    fn act(&mut self, _position: &Vector2, _player_position: &Vector2, _dt: f64) -> Vec<WorldReq> {
+        Vec::new()
+    }
+}
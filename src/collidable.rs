@@ -1,8 +1,63 @@
 use crate::collidable_object::CollidableObject;
+use crate::config::CollisionConfig;
+use crate::config::CollisionResponse;
 use crate::game_object::GameObject;
 use crate::object_type::ObjectType;
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
 
+// Names one collidable for as long as the simulation runs; handles are never reused.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CollidableHandle(pub usize);
+
+// What one collidable learns when it runs into another.
+pub struct Collision {
+    pub other_handle: CollidableHandle,
+    pub other_object_type: ObjectType,
+    // See `Collidable::kind_names`.
+    pub other_kind_names: Vec<String>,
+    // Where they touched, on the other object's surface.
+    pub point: Vector2,
+    // A unit vector out of the other object's surface, towards whoever is being told about the collision.
+    pub normal: Vector2,
+    // What was done to whoever is being told about the collision. `Ignore` if nothing was.
+    pub response: CollisionResponse,
+}
+
+// How things react to collisions is mostly up to `CollisionConfig`; the simulation moves, stops and removes
+// collidables through these. `collide` is for anything a type does on top of that.
 pub trait Collidable: GameObject {
     fn get_collidable_object(&self) -> &CollidableObject;
-    fn collide(&mut self, other_object_type: ObjectType);
+    fn get_velocity(&self) -> Vector2;
+    fn set_velocity(&mut self, velocity: Vector2);
+    fn set_position(&mut self, position: Vector2);
+    // Anything it asks for, like new bullets, is added once the collision pass is over.
+    fn collide(&mut self, collision: &Collision) -> Vec<WorldReq>;
+
+    // Called when it runs into something the table says destroys it. Things that take more than one hit, or can
+    // shrug some off, return true to stay in the level. `direction_to_hitter` is a unit vector.
+    fn survives_hit(&mut self, _direction_to_hitter: Vector2, _hitter_kind_names: &[String]) -> bool {
+        false
+    }
+
+    // What enemy armor can be told to give way to: its object type's name, then any more particular ones, like the
+    // gun definition or bullet kind.
+    fn kind_names(&self) -> Vec<String> {
+        vec![String::from(self.get_object_type().name())]
+    }
+
+    // Skips running into something it has already dealt with, like an enemy a bullet has gone through.
+    fn passes_through(&self, _other_handle: CollidableHandle) -> bool {
+        false
+    }
+
+    // Things that never move go in the simulation's static grid.
+    fn is_static(&self) -> bool {
+        false
+    }
+
+    // How this reacts to running into something. Most things do whatever the config's table says.
+    fn collision_response(&self, other_object_type: ObjectType, collision_config: &CollisionConfig) -> CollisionResponse {
+        collision_config.response(self.get_object_type(), other_object_type)
+    }
 }
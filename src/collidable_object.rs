@@ -1,4 +1,7 @@
+use crate::config::ColliderShape;
+
 pub struct CollidableObject {
     pub width: f64,
     pub height: f64,
+    pub shape: ColliderShape,
 }
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use ncollide2d::bounding_volume::aabb::AABB;
+
+// Buckets things by which cells of a uniform grid their bounding boxes cover, so a collision pass only has to test
+// things that share a cell instead of every pair.
+pub struct CollisionGrid<T> {
+    cell_width: f64,
+    cell_height: f64,
+    // In the order they were inserted. `cells` holds indices into this, so it's rebuilt whenever entries are removed.
+    entries: Vec<(AABB<f64>, T)>,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl<T> CollisionGrid<T> {
+    pub fn new(cell_width: f64, cell_height: f64) -> CollisionGrid<T> {
+        CollisionGrid {
+            cell_width,
+            cell_height,
+            entries: Vec::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, aabb: AABB<f64>, item: T) {
+        let entry_index = self.entries.len();
+        for cell in self.cells_covered_by(&aabb) {
+            self.cells.entry(cell).or_default().push(entry_index);
+        }
+        self.entries.push((aabb, item));
+    }
+
+    // Everything whose bounding box might overlap `aabb`, each once, in the order they were inserted.
+    pub fn query(&self, aabb: &AABB<f64>) -> Vec<&(AABB<f64>, T)> {
+        let mut entry_indices: Vec<usize> = self.cells_covered_by(aabb).filter_map(|cell| self.cells.get(&cell)).flatten().cloned().collect();
+        entry_indices.sort();
+        entry_indices.dedup();
+        entry_indices.into_iter().map(|entry_index| &self.entries[entry_index]).collect()
+    }
+
+    // Things come and go (doors do every time they're switched), so removing them really frees them up.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        let num_entries = self.entries.len();
+        self.entries.retain(|(_, item)| keep(item));
+        if self.entries.len() == num_entries {
+            return;
+        }
+
+        let mut cells: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (entry_index, (aabb, _)) in self.entries.iter().enumerate() {
+            for cell in self.cells_covered_by(aabb) {
+                cells.entry(cell).or_default().push(entry_index);
+            }
+        }
+        self.cells = cells;
+    }
+
+    fn cells_covered_by(&self, aabb: &AABB<f64>) -> impl Iterator<Item = (i64, i64)> {
+        let min_column = (aabb.mins().x / self.cell_width).floor() as i64;
+        let max_column = (aabb.maxs().x / self.cell_width).floor() as i64;
+        let min_row = (aabb.mins().y / self.cell_height).floor() as i64;
+        let max_row = (aabb.maxs().y / self.cell_height).floor() as i64;
+        (min_column..=max_column).flat_map(move |column| (min_row..=max_row).map(move |row| (column, row)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::geometry::Point2;
+
+    fn cell_aabb(column: f64, row: f64) -> AABB<f64> {
+        AABB::new(Point2::new(column * 10.0 + 1.0, row * 10.0 + 1.0), Point2::new(column * 10.0 + 9.0, row * 10.0 + 9.0))
+    }
+
+    #[test]
+    fn query_finds_only_nearby_entries() {
+        let mut grid = CollisionGrid::new(10.0, 10.0);
+        grid.insert(cell_aabb(0.0, 0.0), "near");
+        grid.insert(cell_aabb(5.0, 5.0), "far");
+
+        let found: Vec<&str> = grid.query(&cell_aabb(0.0, 0.0)).into_iter().map(|(_, item)| *item).collect();
+        assert_eq!(found, vec!["near"]);
+    }
+
+    #[test]
+    fn retain_frees_removed_entries() {
+        let mut grid = CollisionGrid::new(10.0, 10.0);
+        grid.insert(cell_aabb(0.0, 0.0), 0);
+        // Like a door opening and closing over and over.
+        for door in 1..100 {
+            grid.insert(cell_aabb(1.0, 0.0), door);
+            grid.retain(|&item| item == 0);
+        }
+        grid.insert(cell_aabb(1.0, 0.0), 100);
+
+        assert_eq!(grid.entries.len(), 2);
+        assert!(grid.cells.values().all(|entry_indices| entry_indices.iter().all(|&entry_index| entry_index < 2)));
+        let found: Vec<i32> = grid.query(&AABB::new(Point2::new(0.0, 0.0), Point2::new(19.0, 9.0))).into_iter().map(|(_, item)| *item).collect();
+        assert_eq!(found, vec![0, 100]);
+    }
+}
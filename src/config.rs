@@ -1,11 +1,816 @@
 use crate::serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::path::PathBuf;
 
-#[derive(Serialize, Deserialize, Debug)]
+use crate::error::Error;
+use crate::object_type::ObjectType;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
-    pub gunaxe_gun_config: GunConfig,
+    // Every kind of gun a level can hand out, by the name levels use for it.
+    #[serde(default = "default_gun_definitions")]
+    pub gun_definitions: BTreeMap<String, GunDefinition>,
+    #[serde(default)]
+    pub tuning_config: TuningConfig,
+    #[serde(default)]
+    pub collider_config: ColliderConfig,
+    #[serde(default)]
+    pub collision_config: CollisionConfig,
+    #[serde(default)]
+    pub enemy_config: EnemyConfig,
+    #[serde(default)]
+    pub wall_config: WallConfig,
+    #[serde(default)]
+    pub game_loop_config: GameLoopConfig,
+    #[serde(default)]
+    pub replay_config: ReplayConfig,
+    #[serde(default)]
+    pub solver_config: SolverConfig,
+    #[serde(default)]
+    pub level_config: LevelConfig,
+    #[serde(default)]
+    pub asset_config: AssetConfig,
+}
+
+// How the game feels. Sizes and speeds are in pixels; levels can override everything but the window size.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TuningConfig {
+    pub window_width: u32,
+    pub window_height: u32,
+    // Cells across and down. The window is split evenly between them.
+    pub grid_width: u32,
+    pub grid_height: u32,
+    pub player_scale: f64,
+    pub wall_scale: f64,
+    pub enemy_scale: f64,
+    pub ground_scale: f64,
+    pub gun_scale: f64,
+    pub bullet_scale: f64,
+    pub bullet_speed: f64,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        TuningConfig {
+            window_width: 1280,
+            window_height: 720,
+            grid_width: 32,
+            grid_height: 18,
+            player_scale: 0.5,
+            wall_scale: 1.0,
+            enemy_scale: 1.0,
+            ground_scale: 1.0,
+            gun_scale: 0.5,
+            bullet_scale: 0.03125,
+            bullet_speed: 200.0,
+        }
+    }
+}
+
+// A level's tweaks to the `TuningConfig`. Anything left out keeps the config's value.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TuningOverrides {
+    pub grid_width: Option<u32>,
+    pub grid_height: Option<u32>,
+    pub player_scale: Option<f64>,
+    pub wall_scale: Option<f64>,
+    pub enemy_scale: Option<f64>,
+    pub ground_scale: Option<f64>,
+    pub gun_scale: Option<f64>,
+    pub bullet_scale: Option<f64>,
+    pub bullet_speed: Option<f64>,
+}
+
+impl TuningConfig {
+    pub fn with_overrides(&self, overrides: &TuningOverrides) -> TuningConfig {
+        TuningConfig {
+            window_width: self.window_width,
+            window_height: self.window_height,
+            grid_width: overrides.grid_width.unwrap_or(self.grid_width),
+            grid_height: overrides.grid_height.unwrap_or(self.grid_height),
+            player_scale: overrides.player_scale.unwrap_or(self.player_scale),
+            wall_scale: overrides.wall_scale.unwrap_or(self.wall_scale),
+            enemy_scale: overrides.enemy_scale.unwrap_or(self.enemy_scale),
+            ground_scale: overrides.ground_scale.unwrap_or(self.ground_scale),
+            gun_scale: overrides.gun_scale.unwrap_or(self.gun_scale),
+            bullet_scale: overrides.bullet_scale.unwrap_or(self.bullet_scale),
+            bullet_speed: overrides.bullet_speed.unwrap_or(self.bullet_speed),
+        }
+    }
+
+    pub fn cell_width(&self) -> u32 {
+        self.window_width / self.grid_width
+    }
+
+    pub fn cell_height(&self) -> u32 {
+        self.window_height / self.grid_height
+    }
+
+    // Everything that would make the game unplayable, or crash it.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.window_width == 0 || self.window_height == 0 {
+            problems.push(format!("the window can't be {} by {}", self.window_width, self.window_height));
+        }
+        if self.grid_width == 0 || self.grid_height == 0 {
+            problems.push(format!("the grid can't be {} by {}", self.grid_width, self.grid_height));
+        } else if self.grid_width > self.window_width || self.grid_height > self.window_height {
+            problems.push(format!("a {} by {} grid doesn't fit in a {} by {} window", self.grid_width, self.grid_height, self.window_width, self.window_height));
+        }
+        let positives = [
+            ("player_scale", self.player_scale),
+            ("wall_scale", self.wall_scale),
+            ("enemy_scale", self.enemy_scale),
+            ("ground_scale", self.ground_scale),
+            ("gun_scale", self.gun_scale),
+            ("bullet_scale", self.bullet_scale),
+            ("bullet_speed", self.bullet_speed),
+        ];
+        for &(name, value) in &positives {
+            if !(value.is_finite() && value > 0.0) {
+                problems.push(format!("{} must be a positive number, not {}", name, value));
+            }
+        }
+        problems
+    }
+}
+
+// The outline things collide with, sized to their texture and turned with them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum ColliderShape {
+    // The texture's rectangle.
+    Box,
+    // The biggest circle that fits in the texture.
+    Circle,
+    // A convex outline. Points are in half texture sizes from the middle, so [1.0, 1.0] is a corner.
+    Polygon { points: Vec<[f64; 2]> },
+}
+
+impl ColliderShape {
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let ColliderShape::Polygon { points } = self {
+            if points.iter().any(|point| !(point[0].is_finite() && point[1].is_finite())) {
+                problems.push(String::from("polygon points must be numbers"));
+            } else if !encloses_area(points) {
+                problems.push(String::from("a polygon needs at least 3 points that aren't in a line"));
+            }
+        }
+        problems
+    }
+}
+
+// Whether the points span a triangle, rather than all sitting on one line.
+fn encloses_area(points: &[[f64; 2]]) -> bool {
+    let first = match points.first() {
+        Some(first) => first,
+        None => return false,
+    };
+    let second = match points.iter().find(|point| (point[0] - first[0]).abs() > 1e-6 || (point[1] - first[1]).abs() > 1e-6) {
+        Some(second) => second,
+        None => return false,
+    };
+    points.iter().any(|third| ((second[0] - first[0]) * (third[1] - first[1]) - (second[1] - first[1]) * (third[0] - first[0])).abs() > 1e-6)
+}
+
+// Which collider shape each kind of collidable object gets.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColliderConfig {
+    pub wall: ColliderShape,
+    pub enemy: ColliderShape,
+    pub gun: ColliderShape,
+    pub bullet: ColliderShape,
+    #[serde(default = "default_player_collider")]
+    pub player: ColliderShape,
+    #[serde(default = "default_hostage_collider")]
+    pub hostage: ColliderShape,
+}
+
+fn default_player_collider() -> ColliderShape {
+    ColliderShape::Circle
+}
+
+fn default_hostage_collider() -> ColliderShape {
+    ColliderShape::Box
+}
+
+impl Default for ColliderConfig {
+    fn default() -> Self {
+        ColliderConfig {
+            wall: ColliderShape::Box,
+            enemy: ColliderShape::Box,
+            gun: ColliderShape::Box,
+            bullet: ColliderShape::Circle,
+            player: default_player_collider(),
+            hostage: default_hostage_collider(),
+        }
+    }
+}
+
+impl ColliderConfig {
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for &(name, shape) in &[("wall", &self.wall), ("enemy", &self.enemy), ("gun", &self.gun), ("bullet", &self.bullet), ("player", &self.player), ("hostage", &self.hostage)] {
+            problems.extend(shape.validate().into_iter().map(|problem| format!("collider_config.{}: {}", name, problem)));
+        }
+        problems
+    }
+}
+
+// What happens to something when it runs into something else.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionResponse {
+    // It's removed from the level.
+    Destroy,
+    // It stays where it touched.
+    Stop,
+    // It bounces off, like light off a mirror.
+    Bounce,
+    // It passes straight through.
+    Ignore,
+}
+
+// Which kinds of objects react to which. `responses[a][b]` is what happens to an `a` when it runs into a `b`;
+// anything left out is ignored, and pairs where both sides ignore each other aren't even tested.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CollisionConfig {
+    pub responses: BTreeMap<ObjectType, BTreeMap<ObjectType, CollisionResponse>>,
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        let mut responses = BTreeMap::new();
+        let rules = [
+            (ObjectType::Bullet, ObjectType::Wall, CollisionResponse::Destroy),
+            (ObjectType::Bullet, ObjectType::Enemy, CollisionResponse::Destroy),
+            (ObjectType::Gun, ObjectType::Wall, CollisionResponse::Destroy),
+            (ObjectType::LethalGun, ObjectType::Wall, CollisionResponse::Destroy),
+            (ObjectType::Enemy, ObjectType::Bullet, CollisionResponse::Destroy),
+            (ObjectType::Enemy, ObjectType::LethalGun, CollisionResponse::Destroy),
+            (ObjectType::Enemy, ObjectType::Wall, CollisionResponse::Stop),
+            (ObjectType::EnemyBullet, ObjectType::Wall, CollisionResponse::Destroy),
+            (ObjectType::EnemyBullet, ObjectType::Player, CollisionResponse::Destroy),
+            (ObjectType::Player, ObjectType::EnemyBullet, CollisionResponse::Destroy),
+            (ObjectType::Player, ObjectType::Enemy, CollisionResponse::Destroy),
+            (ObjectType::Bullet, ObjectType::Hostage, CollisionResponse::Destroy),
+            (ObjectType::Hostage, ObjectType::Bullet, CollisionResponse::Destroy),
+            (ObjectType::Hostage, ObjectType::LethalGun, CollisionResponse::Destroy),
+            (ObjectType::Bullet, ObjectType::Door, CollisionResponse::Destroy),
+            (ObjectType::Gun, ObjectType::Door, CollisionResponse::Destroy),
+            (ObjectType::LethalGun, ObjectType::Door, CollisionResponse::Destroy),
+            (ObjectType::Enemy, ObjectType::Door, CollisionResponse::Stop),
+            (ObjectType::EnemyBullet, ObjectType::Door, CollisionResponse::Destroy),
+            (ObjectType::Bullet, ObjectType::Switch, CollisionResponse::Destroy),
+            (ObjectType::Gun, ObjectType::Switch, CollisionResponse::Destroy),
+            (ObjectType::LethalGun, ObjectType::Switch, CollisionResponse::Destroy),
+            (ObjectType::Enemy, ObjectType::Switch, CollisionResponse::Stop),
+            (ObjectType::EnemyBullet, ObjectType::Switch, CollisionResponse::Destroy),
+            (ObjectType::Switch, ObjectType::Bullet, CollisionResponse::Destroy),
+            (ObjectType::Switch, ObjectType::Gun, CollisionResponse::Destroy),
+            (ObjectType::Switch, ObjectType::LethalGun, CollisionResponse::Destroy),
+            (ObjectType::Bullet, ObjectType::BreakableWall, CollisionResponse::Destroy),
+            (ObjectType::Gun, ObjectType::BreakableWall, CollisionResponse::Destroy),
+            (ObjectType::LethalGun, ObjectType::BreakableWall, CollisionResponse::Destroy),
+            (ObjectType::Enemy, ObjectType::BreakableWall, CollisionResponse::Stop),
+            (ObjectType::EnemyBullet, ObjectType::BreakableWall, CollisionResponse::Destroy),
+            (ObjectType::BreakableWall, ObjectType::Bullet, CollisionResponse::Destroy),
+            (ObjectType::BreakableWall, ObjectType::LethalGun, CollisionResponse::Destroy),
+            (ObjectType::Gun, ObjectType::GlassWall, CollisionResponse::Destroy),
+            (ObjectType::LethalGun, ObjectType::GlassWall, CollisionResponse::Destroy),
+            (ObjectType::Enemy, ObjectType::GlassWall, CollisionResponse::Stop),
+            (ObjectType::Bullet, ObjectType::BumperWall, CollisionResponse::Destroy),
+            (ObjectType::Gun, ObjectType::BumperWall, CollisionResponse::Bounce),
+            (ObjectType::LethalGun, ObjectType::BumperWall, CollisionResponse::Bounce),
+            (ObjectType::Enemy, ObjectType::BumperWall, CollisionResponse::Stop),
+            (ObjectType::EnemyBullet, ObjectType::BumperWall, CollisionResponse::Destroy),
+        ];
+        for &(object_type, other_object_type, response) in &rules {
+            responses.entry(object_type).or_insert_with(BTreeMap::new).insert(other_object_type, response);
+        }
+        CollisionConfig {
+            responses,
+        }
+    }
+}
+
+impl CollisionConfig {
+    pub fn response(&self, object_type: ObjectType, other_object_type: ObjectType) -> CollisionResponse {
+        self.responses.get(&object_type).and_then(|responses| responses.get(&other_object_type)).cloned().unwrap_or(CollisionResponse::Ignore)
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (object_type, responses) in &self.responses {
+            for (other_object_type, response) in responses {
+                if *object_type == ObjectType::Ground || *other_object_type == ObjectType::Ground {
+                    problems.push(String::from("collision_config: a ground never collides with anything"));
+                }
+                if object_type.never_moves() && (*response == CollisionResponse::Stop || *response == CollisionResponse::Bounce) {
+                    problems.push(format!("collision_config: a {} never moves, so it can only be destroyed by or ignore a {}", object_type.name(), other_object_type.name()));
+                }
+            }
+        }
+        problems.dedup();
+        problems
+    }
+}
+
+// Cells that always mean something other than an enemy: wall, breakable, glass and bumper wall, player, hostage, switch,
+// closed and open door, and ground.
+pub const NON_ENEMY_CELLS: [char; 10] = ['W', 'B', 'G', 'R', 'P', 'C', 'L', 'D', 'O', '_'];
+
+// The walls levels can place besides plain ones, which never break and stop everything.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WallConfig {
+    // Hits a breakable wall takes to come down.
+    pub breakable_health: u32,
+    pub breakable_texture: String,
+    pub glass_texture: String,
+    pub bumper_texture: String,
+}
+
+impl Default for WallConfig {
+    fn default() -> Self {
+        WallConfig {
+            breakable_health: 3,
+            breakable_texture: String::from("textures/breakable_wall.png"),
+            glass_texture: String::from("textures/glass_wall.png"),
+            bumper_texture: String::from("textures/bumper_wall.png"),
+        }
+    }
+}
+
+impl WallConfig {
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.breakable_health == 0 {
+            problems.push(String::from("wall_config.breakable_health must be at least 1"));
+        }
+        problems
+    }
+}
+
+// How fast enemies that move go and how turrets shoot. Levels say which enemies patrol, chase or shoot.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EnemyConfig {
+    // Pixels per second.
+    pub patrol_speed: f64,
+    pub chase_speed: f64,
+    // Seconds between a turret's shots. The first comes this long after the level starts.
+    pub turret_fire_interval: f64,
+    pub turret_bullet_speed: f64,
+    pub turret_bullet_texture: String,
+    pub turret_bullet_sound: String,
+    // The kinds of enemy levels can place, by the cell code that places them.
+    #[serde(default = "default_enemy_variants")]
+    pub variants: BTreeMap<String, EnemyVariant>,
+}
+
+// How tough one kind of enemy is.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EnemyVariant {
+    pub texture: String,
+    // Hits it takes to kill.
+    pub health: u32,
+    // Until something named here hits it, hits do nothing. The names are object types, like `lethal_gun`, gun
+    // definitions, like `gun_axe`, and bullet kinds, like `explosive`. Empty for no armor.
+    #[serde(default)]
+    pub armor_broken_by: Vec<String>,
+    // Hits from within this wide an arc, centred on the way it faces, are blocked. 0 for no shield.
+    #[serde(default)]
+    pub shield_degrees: f64,
+}
+
+impl EnemyVariant {
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.health == 0 {
+            problems.push(String::from("health must be at least 1"));
+        }
+        if !(self.shield_degrees.is_finite() && self.shield_degrees >= 0.0 && self.shield_degrees <= 360.0) {
+            problems.push(format!("shield_degrees must be between 0 and 360, not {}", self.shield_degrees));
+        }
+        problems
+    }
+}
+
+fn enemy_variant(health: u32, armor_broken_by: &[&str], shield_degrees: f64) -> EnemyVariant {
+    EnemyVariant {
+        texture: String::from("textures/enemy.png"),
+        health,
+        armor_broken_by: armor_broken_by.iter().map(|&name| String::from(name)).collect(),
+        shield_degrees,
+    }
+}
+
+fn default_enemy_variants() -> BTreeMap<String, EnemyVariant> {
+    let mut variants = BTreeMap::new();
+    variants.insert(String::from("E"), enemy_variant(1, &[], 0.0));
+    variants.insert(String::from("H"), enemy_variant(3, &[], 0.0));
+    variants.insert(String::from("A"), enemy_variant(1, &["gun_axe"], 0.0));
+    variants.insert(String::from("S"), enemy_variant(1, &[], 120.0));
+    variants
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GunConfig {
-    pub gun_depth: usize,
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        EnemyConfig {
+            patrol_speed: 80.0,
+            chase_speed: 50.0,
+            turret_fire_interval: 2.0,
+            turret_bullet_speed: 200.0,
+            turret_bullet_texture: String::from("textures/bullet.png"),
+            turret_bullet_sound: String::from("sounds/dong.ogg"),
+            variants: default_enemy_variants(),
+        }
+    }
+}
+
+impl EnemyConfig {
+    // The kind of enemy a cell places, if it places one.
+    pub fn variant(&self, cell: char) -> Option<&EnemyVariant> {
+        self.variants.get(&cell.to_string())
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let positives = [
+            ("patrol_speed", self.patrol_speed),
+            ("chase_speed", self.chase_speed),
+            ("turret_fire_interval", self.turret_fire_interval),
+            ("turret_bullet_speed", self.turret_bullet_speed),
+        ];
+        for &(name, value) in &positives {
+            if !(value.is_finite() && value > 0.0) {
+                problems.push(format!("enemy_config.{} must be a positive number, not {}", name, value));
+            }
+        }
+        for (cell_code, variant) in &self.variants {
+            let mut cell_code_chars = cell_code.chars();
+            match (cell_code_chars.next(), cell_code_chars.next()) {
+                (Some(cell), None) if !NON_ENEMY_CELLS.contains(&cell) => {}
+                _ => problems.push(format!("enemy_config.variants: {:?} must be a single character other than {:?}", cell_code, NON_ENEMY_CELLS)),
+            }
+            problems.extend(variant.validate().into_iter().map(|problem| format!("enemy_config.variants.{}: {}", cell_code, problem)));
+        }
+        problems
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerMode {
+    // One gun shot per click.
+    Press,
+    // Keeps shooting guns for as long as the button is held.
+    Held,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulletSource {
+    // Every gun in the chain fires.
+    EveryGun,
+    // Only the guns from the latest shot fire.
+    NewestGuns,
+}
+
+// What a gun's bullets do when they hit things.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BulletKind {
+    // Whatever `collision_config` says for bullets.
+    Normal,
+    // Bounces off walls up to `max_bounces` times, losing a fraction of its speed each time, then acts like a normal bullet.
+    Ricochet { max_bounces: u32, speed_loss_per_bounce: f64 },
+    // Goes straight through enemies, killing them, and only stops at walls.
+    Piercing,
+    // When it's destroyed, so is everything within `radius` pixels that a bullet would destroy.
+    Explosive { radius: f64 },
+    // Slower and bigger than a normal bullet, by these factors.
+    Heavy { speed_multiplier: f64, scale_multiplier: f64 },
+    // Breaks into `count` normal bullets when it hits a wall, bouncing off it in a fan `spread_degrees` wide.
+    Split { count: usize, spread_degrees: f64 },
+}
+
+// Spelled out rather than derived so older compilers that the pinned nalgebra and ncollide2d need can build it.
+#[allow(clippy::derivable_impls)]
+impl Default for BulletKind {
+    fn default() -> Self {
+        BulletKind::Normal
+    }
+}
+
+impl BulletKind {
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        match self {
+            BulletKind::Normal | BulletKind::Piercing => {}
+            BulletKind::Ricochet { speed_loss_per_bounce, .. } => {
+                if !(*speed_loss_per_bounce >= 0.0 && *speed_loss_per_bounce < 1.0) {
+                    problems.push(format!("speed_loss_per_bounce must be at least 0 and less than 1, not {}", speed_loss_per_bounce));
+                }
+            }
+            BulletKind::Explosive { radius } => {
+                if !(radius.is_finite() && *radius > 0.0) {
+                    problems.push(format!("radius must be a positive number, not {}", radius));
+                }
+            }
+            BulletKind::Heavy { speed_multiplier, scale_multiplier } => {
+                if !(speed_multiplier.is_finite() && *speed_multiplier > 0.0) {
+                    problems.push(format!("speed_multiplier must be a positive number, not {}", speed_multiplier));
+                }
+                if !(scale_multiplier.is_finite() && *scale_multiplier > 0.0) {
+                    problems.push(format!("scale_multiplier must be a positive number, not {}", scale_multiplier));
+                }
+            }
+            BulletKind::Split { count, spread_degrees } => {
+                if *count == 0 {
+                    problems.push(String::from("count must be at least 1"));
+                }
+                if !(spread_degrees.is_finite() && *spread_degrees >= 0.0 && *spread_degrees <= 360.0) {
+                    problems.push(format!("spread_degrees must be between 0 and 360, not {}", spread_degrees));
+                }
+            }
+        }
+        problems
+    }
+}
+
+// What a gun does when shot and what it looks and sounds like.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GunDefinition {
+    // How many guns a gun shoots out.
+    pub spawn_count: usize,
+    // The fan the new guns are spread evenly across, centred on the gun that shot them.
+    pub spread_degrees: f64,
+    // How far from the gun that shot them the new guns appear.
+    pub spawn_offset: f64,
+    pub projectile_speed: f64,
+    // Radians per second.
+    pub spin_rate: f64,
+    // How many guns may be chained before the bullets have to fire. Levels can override it.
+    #[serde(default)]
+    pub gun_depth: Option<usize>,
+    // Kills enemies it flies into.
+    #[serde(default)]
+    pub lethal_on_contact: bool,
+    pub trigger: TriggerMode,
+    pub bullets_from: BulletSource,
+    // Levels can override it.
+    #[serde(default)]
+    pub bullet_kind: BulletKind,
+    pub gun_texture: String,
+    pub selected_gun_texture: String,
+    pub bullet_texture: String,
+    pub gun_sound: String,
+    pub bullet_sound: String,
+}
+
+impl GunDefinition {
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.spawn_count == 0 {
+            problems.push(String::from("spawn_count must be at least 1"));
+        }
+        if !(self.spread_degrees.is_finite() && self.spread_degrees >= 0.0 && self.spread_degrees <= 360.0) {
+            problems.push(format!("spread_degrees must be between 0 and 360, not {}", self.spread_degrees));
+        }
+        if !(self.spawn_offset.is_finite() && self.spawn_offset >= 0.0) {
+            problems.push(format!("spawn_offset can't be {}", self.spawn_offset));
+        }
+        if !(self.projectile_speed.is_finite() && self.projectile_speed > 0.0) {
+            problems.push(format!("projectile_speed must be a positive number, not {}", self.projectile_speed));
+        }
+        if !self.spin_rate.is_finite() {
+            problems.push(format!("spin_rate can't be {}", self.spin_rate));
+        }
+        if self.gun_depth == Some(0) {
+            problems.push(String::from("gun_depth is 0, so it can never be shot"));
+        }
+        problems.extend(self.bullet_kind.validate());
+        problems
+    }
+}
+
+fn gun_definition(spawn_count: usize, spread_degrees: f64, spawn_offset: f64, trigger: TriggerMode, bullets_from: BulletSource, texture_name: &str) -> GunDefinition {
+    GunDefinition {
+        spawn_count,
+        spread_degrees,
+        spawn_offset,
+        projectile_speed: 75.0,
+        spin_rate: 4.0,
+        gun_depth: None,
+        lethal_on_contact: false,
+        trigger,
+        bullets_from,
+        bullet_kind: BulletKind::Normal,
+        gun_texture: format!("textures/{}.png", texture_name),
+        selected_gun_texture: format!("textures/{}_selected.png", texture_name),
+        bullet_texture: String::from("textures/bullet.png"),
+        gun_sound: String::from("sounds/boom.ogg"),
+        bullet_sound: String::from("sounds/boop.ogg"),
+    }
+}
+
+// The guns the game shipped with, for configs written before guns were data.
+pub fn default_gun_definitions() -> BTreeMap<String, GunDefinition> {
+    let mut gun_definitions = BTreeMap::new();
+    gun_definitions.insert(String::from("hand_gun"), gun_definition(1, 0.0, 30.0, TriggerMode::Press, BulletSource::EveryGun, "GunGunV1"));
+    gun_definitions.insert(
+        String::from("gun_axe"),
+        GunDefinition {
+            gun_depth: Some(5),
+            lethal_on_contact: true,
+            ..gun_definition(1, 0.0, 30.0, TriggerMode::Press, BulletSource::EveryGun, "GunaxeV1")
+        },
+    );
+    gun_definitions.insert(String::from("tommy_gun"), gun_definition(1, 0.0, 30.0, TriggerMode::Held, BulletSource::EveryGun, "shotgun"));
+    gun_definitions.insert(String::from("shot_gun"), gun_definition(2, 45.0, 75.0, TriggerMode::Press, BulletSource::NewestGuns, "shotgun"));
+    gun_definitions
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameLoopConfig {
+    pub ticks_per_second: u32,
+    // Caps how many ticks a single slow frame may run before we give up catching up.
+    pub max_ticks_per_update: u32,
+}
+
+impl Default for GameLoopConfig {
+    fn default() -> Self {
+        GameLoopConfig {
+            ticks_per_second: 120,
+            max_ticks_per_update: 8,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReplayConfig {
+    pub record_replays: bool,
+    pub replay_directory: String,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        ReplayConfig {
+            record_replays: true,
+            replay_directory: String::from("replays"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LevelConfig {
+    // The campaign's chapters and levels, as an asset path.
+    pub level_pack: String,
+    // Any level files dropped in here show up in the menu under "Custom".
+    pub user_levels_directory: String,
+    // Reload the config and the level being played whenever either file is saved, and restart the level.
+    pub hot_reload: bool,
+}
+
+impl Default for LevelConfig {
+    fn default() -> Self {
+        LevelConfig {
+            level_pack: String::from("Levels/pack.json"),
+            user_levels_directory: String::from("levels"),
+            hot_reload: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AssetConfig {
+    // Folders or packed archives to load assets from. Assets in later ones replace those in earlier ones.
+    pub mount_points: Vec<String>,
+}
+
+impl Default for AssetConfig {
+    fn default() -> Self {
+        AssetConfig {
+            mount_points: vec![String::from("assets"), String::from("mods"), String::from("user_assets")],
+        }
+    }
+}
+
+// How finely the level solver searches. Finer steps find trickier solutions but take much longer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SolverConfig {
+    // The solver steps levels at its own rate; its replays carry this rate so they play back the same.
+    pub ticks_per_second: u32,
+    pub angle_step_degrees: f64,
+    pub max_gun_shots: usize,
+    pub chain_delay_step_seconds: f64,
+    pub max_chain_delay_seconds: f64,
+    pub max_bullet_delay_seconds: f64,
+    // How long to wait for bullets and gun axes to finish flying before moving on.
+    pub settle_seconds: f64,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            ticks_per_second: 30,
+            angle_step_degrees: 5.0,
+            max_gun_shots: 3,
+            chain_delay_step_seconds: 0.25,
+            max_chain_delay_seconds: 1.5,
+            max_bullet_delay_seconds: 1.5,
+            settle_seconds: 8.0,
+        }
+    }
+}
+
+impl SolverConfig {
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.ticks_per_second == 0 {
+            problems.push(String::from("solver_config.ticks_per_second must be at least 1"));
+        }
+        if self.max_gun_shots == 0 {
+            problems.push(String::from("solver_config.max_gun_shots must be at least 1"));
+        }
+        // A zero step would never get round the circle, or past the first chain delay.
+        for &(name, value) in &[("angle_step_degrees", self.angle_step_degrees), ("chain_delay_step_seconds", self.chain_delay_step_seconds)] {
+            if !(value.is_finite() && value > 0.0) {
+                problems.push(format!("solver_config.{} must be a positive number, not {}", name, value));
+            }
+        }
+        let durations = [
+            ("max_chain_delay_seconds", self.max_chain_delay_seconds),
+            ("max_bullet_delay_seconds", self.max_bullet_delay_seconds),
+            ("settle_seconds", self.settle_seconds),
+        ];
+        for &(name, value) in &durations {
+            if !(value.is_finite() && value >= 0.0) {
+                problems.push(format!("solver_config.{} must be zero or more, not {}", name, value));
+            }
+        }
+        problems
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = self.tuning_config.validate();
+        problems.extend(self.collider_config.validate());
+        problems.extend(self.collision_config.validate());
+        problems.extend(self.enemy_config.validate());
+        problems.extend(self.wall_config.validate());
+        for (name, gun_definition) in &self.gun_definitions {
+            problems.extend(gun_definition.validate().into_iter().map(|problem| format!("gun {}: {}", name, problem)));
+        }
+        if self.game_loop_config.ticks_per_second == 0 {
+            problems.push(String::from("game_loop_config.ticks_per_second must be at least 1"));
+        }
+        if self.game_loop_config.max_ticks_per_update == 0 {
+            problems.push(String::from("game_loop_config.max_ticks_per_update must be at least 1"));
+        }
+        problems.extend(self.solver_config.validate());
+        problems
+    }
+}
+
+pub fn default_config_path() -> Result<PathBuf, Error> {
+    let config_folder = find_folder::Search::ParentsThenKids(3, 3).for_folder("config").map_err(|err| Error::MalformedConfig {
+        path: String::from("config"),
+        message: err.to_string(),
+    })?;
+    Ok(config_folder.join("default.json"))
+}
+
+pub fn read_default_config() -> Result<Config, Error> {
+    read_config_from_file(default_config_path()?)
+}
+
+pub fn read_config_from_file<P: AsRef<Path>>(path: P) -> Result<Config, Error> {
+    let path_ref = path.as_ref();
+    let path_str = path_ref.display().to_string();
+
+    let file = File::open(path_ref).map_err(|err| Error::MalformedConfig {
+        path: path_str.clone(),
+        message: err.to_string(),
+    })?;
+    let reader = BufReader::new(file);
+    let config: Config = serde_json::from_reader(reader).map_err(|err| Error::MalformedConfig {
+        path: path_str.clone(),
+        message: err.to_string(),
+    })?;
+
+    let problems = config.validate();
+    if !problems.is_empty() {
+        return Err(Error::MalformedConfig {
+            path: path_str,
+            message: problems.join("; "),
+        });
+    }
+
+    Ok(config)
 }
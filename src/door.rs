@@ -0,0 +1,88 @@
+use crate::collidable::Collidable;
+use crate::collidable::Collision;
+use crate::collidable_object::CollidableObject;
+use crate::game_object::GameObject;
+use crate::object_type::ObjectType;
+use crate::renderable::Renderable;
+use crate::renderable_object::RenderableObject;
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
+
+// A wall that switches open and close. An open door isn't drawn or collided with at all.
+// Doors only answer to switches. There are no keys: the player never moves, so there'd be nothing to carry one.
+pub struct Door {
+    pub position: Vector2,
+    pub rotation: f64,
+    pub scale: f64,
+    pub renderable_object: RenderableObject,
+    pub collidable_object: CollidableObject,
+    pub is_visible: bool,
+    pub should_delete: bool,
+    pub is_open: bool,
+    // Whether it's in the simulation's renderables and collidables. Only the simulation changes this, as it takes
+    // opened doors out and puts closed ones back.
+    pub is_added: bool,
+}
+
+impl GameObject for Door {
+    fn get_position(&self) -> &Vector2 {
+        &self.position
+    }
+
+    fn get_rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    fn get_scale(&self) -> f64 {
+        self.scale
+    }
+
+    fn get_should_delete(&self) -> bool {
+        self.should_delete
+    }
+
+    fn set_should_delete(&mut self, should_delete: bool) {
+        self.should_delete = should_delete;
+    }
+
+    fn get_object_type(&self) -> ObjectType {
+        ObjectType::Door
+    }
+
+    // Opening a door takes it out of the level until it's closed again.
+    fn is_in_world(&self) -> bool {
+        !self.is_open
+    }
+}
+
+impl Renderable for Door {
+    fn get_renderable_object(&self) -> &RenderableObject {
+        &self.renderable_object
+    }
+
+    fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+}
+
+impl Collidable for Door {
+    fn get_collidable_object(&self) -> &CollidableObject {
+        &self.collidable_object
+    }
+
+    fn get_velocity(&self) -> Vector2 {
+        Vector2::default()
+    }
+
+    fn set_velocity(&mut self, _velocity: Vector2) {}
+
+    fn set_position(&mut self, _position: Vector2) {}
+
+    fn collide(&mut self, _collision: &Collision) -> Vec<WorldReq> {
+        Vec::new()
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
@@ -1,19 +1,43 @@
 use crate::collidable::Collidable;
+use crate::collidable::Collision;
 use crate::collidable_object::CollidableObject;
+use crate::enemy_behavior::EnemyBehavior;
 use crate::game_object::GameObject;
+use crate::input;
 use crate::object_type::ObjectType;
+use crate::player::Player;
 use crate::renderable::Renderable;
 use crate::renderable_object::RenderableObject;
+use crate::simulation::WorldReq;
+use crate::updatable::Updatable;
 use crate::vector2::Vector2;
+use piston_window::Key;
+use piston_window::MouseButton;
+use piston_window::UpdateArgs;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 pub struct Enemy {
     pub position: Vector2,
     pub rotation: f64,
+    pub previous_position: Vector2,
+    pub previous_rotation: f64,
     pub scale: f64,
     pub renderable_object: RenderableObject,
+    pub velocity: Vector2,
     pub should_delete: bool,
     pub collidable_object: CollidableObject,
     pub is_visible: bool,
+    pub enemy_behavior: Box<EnemyBehavior>,
+    // Who to chase or shoot at.
+    pub player: Rc<RefCell<Player>>,
+    pub max_health: u32,
+    pub health: u32,
+    // See `EnemyVariant`.
+    pub armor_broken_by: Vec<String>,
+    pub is_armored: bool,
+    pub shield_degrees: f64,
 }
 
 impl GameObject for Enemy {
@@ -37,6 +61,14 @@ impl GameObject for Enemy {
         self.should_delete = should_delete
     }
 
+    fn get_previous_position(&self) -> &Vector2 {
+        &self.previous_position
+    }
+
+    fn get_previous_rotation(&self) -> f64 {
+        self.previous_rotation
+    }
+
     fn get_object_type(&self) -> ObjectType {
         ObjectType::Enemy
     }
@@ -52,20 +84,68 @@ impl Renderable for Enemy {
     }
 }
 
+impl Updatable for Enemy {
+    #[allow(unused_variables)]
+    fn update(&mut self, key_states: &HashMap<Key, input::ButtonState>, mouse_states: &HashMap<MouseButton, input::ButtonState>, mouse_pos: &Vector2, args: UpdateArgs) -> Vec<WorldReq> {
+        self.previous_position = self.position;
+        self.previous_rotation = self.rotation;
+
+        let player_position = *self.player.borrow().get_position();
+        self.velocity = self.enemy_behavior.velocity(&self.position, &player_position, args.dt);
+        self.position += self.velocity * args.dt;
+        // Moving enemies face the way they're going.
+        if self.velocity != Vector2::default() {
+            self.rotation = self.velocity.y.atan2(self.velocity.x);
+        }
+
+        self.enemy_behavior.act(&self.position, &player_position, args.dt)
+    }
+}
+
 impl Collidable for Enemy {
     fn get_collidable_object(&self) -> &CollidableObject {
         &self.collidable_object
     }
 
-    fn collide(&mut self, other_object_type: ObjectType) {
-        match other_object_type {
-            ObjectType::Bullet => {
-                self.set_should_delete(true);
-            }
-            ObjectType::GunAxe => {
-                self.set_should_delete(true);
+    fn get_velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    fn set_velocity(&mut self, velocity: Vector2) {
+        self.velocity = velocity
+    }
+
+    fn set_position(&mut self, position: Vector2) {
+        self.position = position
+    }
+
+    fn collide(&mut self, _collision: &Collision) -> Vec<WorldReq> {
+        Vec::new()
+    }
+
+    fn is_static(&self) -> bool {
+        self.enemy_behavior.is_static()
+    }
+
+    // A shield blocks the hit outright. Armor does too, unless the hit is from something that breaks it, in which
+    // case the armor is gone and the hit counts.
+    fn survives_hit(&mut self, direction_to_hitter: Vector2, hitter_kind_names: &[String]) -> bool {
+        let facing = Vector2 {
+            x: self.rotation.cos(),
+            y: self.rotation.sin(),
+        };
+        if self.shield_degrees > 0.0 && facing.dot(&direction_to_hitter) >= (self.shield_degrees / 2.0).to_radians().cos() {
+            return true;
+        }
+
+        if self.is_armored {
+            if !hitter_kind_names.iter().any(|name| self.armor_broken_by.contains(name)) {
+                return true;
             }
-            _ => {}
+            self.is_armored = false;
         }
+
+        self.health = self.health.saturating_sub(1);
+        self.health > 0
     }
 }
@@ -0,0 +1,11 @@
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
+
+pub trait EnemyBehavior {
+    // Enemies that never move go in the simulation's static grid.
+    fn is_static(&self) -> bool;
+    // How fast and which way to move this tick, in pixels per second.
+    fn velocity(&mut self, position: &Vector2, player_position: &Vector2, dt: f64) -> Vector2;
+    // Anything else the enemy does this tick, like shooting.
+    fn act(&mut self, position: &Vector2, player_position: &Vector2, dt: f64) -> Vec<WorldReq>;
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+// Everything that can go wrong loading the game's data. These get shown on the error screen, or printed
+// by the command line tools, instead of taking the game down.
+#[derive(Debug)]
+pub enum Error {
+    // An asset that isn't in any mount point, or couldn't be read.
+    MissingAsset { path: String, message: String },
+    // An asset that was found but couldn't be decoded.
+    BadAsset { path: String, message: String },
+    MalformedConfig { path: String, message: String },
+    // A level file that doesn't parse. Lines and columns are counted from 1, when we know them.
+    LevelParse { path: String, line: Option<usize>, column: Option<usize>, message: String },
+    // A level that parses but can't be built, like one with no gun concepts.
+    InvalidLevel { level: String, message: String },
+    // A replay that can't be read or saved, or was recorded by a different version of the simulation.
+    Replay { path: String, message: String },
+    // A command line argument that doesn't make sense, like a number that isn't one.
+    BadArgument { argument: String, message: String },
+    // Packing a directory of assets into an archive went wrong partway.
+    Pack { path: String, message: String },
+}
+
+impl Error {
+    pub fn missing_asset(path: &str, err: &fmt::Display) -> Error {
+        Error::MissingAsset {
+            path: String::from(path),
+            message: err.to_string(),
+        }
+    }
+
+    pub fn bad_asset(path: &str, err: &fmt::Display) -> Error {
+        Error::BadAsset {
+            path: String::from(path),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MissingAsset { path, message } => write!(f, "Couldn't find asset {}: {}", path, message),
+            Error::BadAsset { path, message } => write!(f, "Couldn't load asset {}: {}", path, message),
+            Error::MalformedConfig { path, message } => write!(f, "Couldn't read config {}: {}", path, message),
+            Error::LevelParse { path, line, column, message } => match (line, column) {
+                (Some(line), Some(column)) => write!(f, "Couldn't parse level {} at line {}, column {}: {}", path, line, column, message),
+                (Some(line), None) => write!(f, "Couldn't parse level {} at line {}: {}", path, line, message),
+                _ => write!(f, "Couldn't parse level {}: {}", path, message),
+            },
+            Error::InvalidLevel { level, message } => write!(f, "Level {} {}", level, message),
+            Error::Replay { path, message } => write!(f, "Replay {} {}", path, message),
+            Error::BadArgument { argument, message } => write!(f, "Bad argument {}: {}", argument, message),
+            Error::Pack { path, message } => write!(f, "Couldn't pack assets into {}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
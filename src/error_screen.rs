@@ -0,0 +1,68 @@
+use crate::game_state::GameState;
+use crate::game_state::GameStateType;
+use crate::game_state::UpdateResult;
+use crate::game_state::UPDATE_RESULT_RUNNING;
+use crate::game_state::UPDATE_RESULT_SUCCESS;
+use crate::game_state_utils;
+use crate::input;
+use crate::ui_bundle::UiBundle;
+use crate::vector2::Vector2;
+use conrod_core::color::Colorable;
+use conrod_core::position::Positionable;
+use conrod_core::position::Sizeable;
+use conrod_core::widget::Widget;
+use piston_window::Context;
+use piston_window::G2d;
+use piston_window::G2dTexture;
+use piston_window::Key;
+use piston_window::MouseButton;
+use piston_window::UpdateArgs;
+use std::collections::HashMap;
+
+// Shown instead of crashing when a level or asset won't load. Clicking goes back to the menu.
+pub struct ErrorScreen {
+    pub message: String,
+    pub image_map: conrod_core::image::Map<G2dTexture>,
+}
+
+impl GameState for ErrorScreen {
+    fn render(&mut self, c: Context, gl: &mut G2d, ui_bundle: &mut UiBundle) {
+        ui_bundle.render_ui(c, gl, &self.image_map);
+    }
+
+    #[allow(unused_variables)]
+    fn update(
+        &mut self,
+        key_states: &HashMap<Key, input::ButtonState>,
+        mouse_states: &HashMap<MouseButton, input::ButtonState>,
+        mouse_pos: &Vector2,
+        ui_bundle: &mut UiBundle,
+        args: UpdateArgs,
+    ) -> UpdateResult {
+        let mut ui_cell = ui_bundle.conrod_ui.set_widgets();
+
+        conrod_core::widget::Canvas::new().pad(30.0).color(conrod_core::color::TRANSPARENT).scroll_kids_vertically().set(ui_bundle.ids.canvas, &mut ui_cell);
+
+        // Error messages can be long, so wrap them to the window.
+        let text = format!("{}\n\nClick to go back to the menu.", self.message);
+        let text_width = ui_cell.win_w - 120.0;
+        conrod_core::widget::Text::new(&text)
+            .font_size(24)
+            .color(conrod_core::color::WHITE)
+            .w(text_width)
+            .center_justify()
+            .wrap_by_word()
+            .middle_of(ui_bundle.ids.canvas)
+            .set(ui_bundle.ids.title, &mut ui_cell);
+
+        if game_state_utils::did_click(&mouse_states) {
+            UPDATE_RESULT_SUCCESS
+        } else {
+            UPDATE_RESULT_RUNNING
+        }
+    }
+
+    fn get_type(&self) -> GameStateType {
+        GameStateType::Error
+    }
+}
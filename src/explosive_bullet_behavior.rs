@@ -0,0 +1,30 @@
+use crate::bullet::Bullet;
+use crate::bullet_behavior::BulletBehavior;
+use crate::collidable::Collision;
+use crate::collidable::Collidable;
+use crate::config::CollisionResponse;
+use crate::simulation::Explosion;
+use crate::simulation::WorldReq;
+
+pub struct ExplosiveBulletBehavior {
+    pub radius: f64,
+}
+
+impl BulletBehavior for ExplosiveBulletBehavior {
+    fn name(&self) -> &str {
+        "explosive"
+    }
+
+    // Goes off where it hit, not wherever it would have got to by the end of the tick.
+    fn collide(&self, bullet: &mut Bullet, collision: &Collision) -> Vec<WorldReq> {
+        if collision.response == CollisionResponse::Destroy {
+            vec![WorldReq::explode(Explosion {
+                position: collision.point,
+                radius: self.radius,
+                kind_names: bullet.kind_names(),
+            })]
+        } else {
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+// Don't stat the files every tick; a few times a second is plenty to feel instant after a save.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Notices when any of a handful of files is saved, by polling their modification times.
+pub struct FileWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    last_poll: Instant,
+}
+
+impl FileWatcher {
+    pub fn new(paths: Vec<PathBuf>) -> FileWatcher {
+        FileWatcher {
+            files: paths.into_iter().map(|path| {
+                let modified = modified_time(&path);
+                (path, modified)
+            }).collect(),
+            last_poll: Instant::now(),
+        }
+    }
+
+    // True once for each poll that finds a file changed, appeared or went missing since the last one.
+    pub fn poll(&mut self) -> bool {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+
+        let mut changed = false;
+        for (path, last_modified) in &mut self.files {
+            let modified = modified_time(path);
+            if modified != *last_modified {
+                *last_modified = modified;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
@@ -0,0 +1,40 @@
+// Turns the variable frame dt piston hands us into a whole number of fixed-length ticks,
+// so the simulation steps the same way no matter how fast we render.
+pub struct FixedTimestep {
+    pub tick_duration: f64,
+    pub max_ticks_per_update: u32,
+    pub accumulator: f64,
+}
+
+impl FixedTimestep {
+    pub fn new(ticks_per_second: u32, max_ticks_per_update: u32) -> FixedTimestep {
+        FixedTimestep {
+            tick_duration: 1.0 / f64::from(ticks_per_second),
+            max_ticks_per_update,
+            accumulator: 0.0,
+        }
+    }
+
+    // Returns how many ticks to run for this frame.
+    pub fn advance(&mut self, dt: f64) -> u32 {
+        self.accumulator += dt;
+
+        let mut num_ticks = 0;
+        while self.accumulator >= self.tick_duration && num_ticks < self.max_ticks_per_update {
+            self.accumulator -= self.tick_duration;
+            num_ticks += 1;
+        }
+
+        // If we fell too far behind, drop the backlog instead of trying to catch up forever.
+        if num_ticks == self.max_ticks_per_update {
+            self.accumulator = self.accumulator.min(self.tick_duration);
+        }
+
+        num_ticks
+    }
+
+    // How far we are between the last tick and the next one, in [0, 1].
+    pub fn alpha(&self) -> f64 {
+        (self.accumulator / self.tick_duration).min(1.0)
+    }
+}
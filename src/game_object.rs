@@ -8,4 +8,20 @@ pub trait GameObject {
     fn get_should_delete(&self) -> bool;
     fn set_should_delete(&mut self, should_delete: bool);
     fn get_object_type(&self) -> ObjectType;
+
+    // Where the object was before the last tick. Moving objects override these so the
+    // renderer can interpolate between ticks; everything else just stays put.
+    fn get_previous_position(&self) -> &Vector2 {
+        self.get_position()
+    }
+
+    fn get_previous_rotation(&self) -> f64 {
+        self.get_rotation()
+    }
+
+    // Whether it belongs in the simulation's lists right now. Unlike a deleted object, one that's out of the world
+    // can come back, like a door that's opened and closed again.
+    fn is_in_world(&self) -> bool {
+        true
+    }
 }
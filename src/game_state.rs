@@ -39,6 +39,7 @@ pub enum GameStateType {
     WorldSelect,
     World,
     Victory,
+    Error,
 }
 
 pub trait GameState {
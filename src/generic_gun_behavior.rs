@@ -1,20 +1,30 @@
+use crate::collidable_object::CollidableObject;
+use crate::config::GunDefinition;
+use crate::game_object::GameObject;
+use crate::gun::Gun;
 use crate::gun_behavior::GunBehavior;
 use crate::object_type::ObjectType;
-use crate::game_object::GameObject;
+use crate::renderable_object::RenderableObject;
+use crate::vector2::Vector2;
 use piston_window::ImageSize;
-use ears::AudioController;
-use crate::gun::{Gun, PROJECTILE_VELOCITY_MAGNITUDE, GUN_SCALE};
-use std::rc::Rc;
 use std::cell::RefCell;
-use crate::vector2::Vector2;
-use crate::renderable_object::RenderableObject;
-use crate::collidable_object::CollidableObject;
+use std::rc::Rc;
 
-pub struct ShotGunBehavior {
+// Any gun described by a `GunDefinition`.
+pub struct GenericGunBehavior {
+    // What the config's `gun_definitions` call it.
+    pub name: String,
+    pub definition: Rc<GunDefinition>,
+    // The definition's depth, unless the level overrides it.
+    pub gun_depth: Option<usize>,
     pub should_delete: bool,
 }
 
-impl GunBehavior for ShotGunBehavior {
+impl GunBehavior for GenericGunBehavior {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
     fn get_should_delete(&self) -> bool {
         self.should_delete
     }
@@ -24,43 +34,51 @@ impl GunBehavior for ShotGunBehavior {
     }
 
     fn get_object_type(&self) -> ObjectType {
-        ObjectType::ShotGun
-    }
-
-    fn collide(&mut self, other_object_type: ObjectType) {
-        if other_object_type == ObjectType::Wall {
-            self.set_should_delete(true);
+        if self.definition.lethal_on_contact {
+            ObjectType::LethalGun
+        } else {
+            ObjectType::Gun
         }
     }
 
     fn new_gun_behavior(&self) -> Box<GunBehavior> {
-        Box::new(ShotGunBehavior {
+        Box::new(GenericGunBehavior {
+            name: self.name.clone(),
+            definition: self.definition.clone(),
+            gun_depth: self.gun_depth,
             should_delete: false,
         })
     }
 
     fn has_gun_depth(&self) -> bool {
-        false
+        self.gun_depth.is_some()
     }
 
     fn get_gun_depth(&self) -> usize {
-        0
+        self.gun_depth.unwrap_or(0)
+    }
+
+    fn spin_rate(&self) -> f64 {
+        self.definition.spin_rate
     }
 
     fn shoot_gun(&self, gun: &Gun) -> Vec<Rc<RefCell<Gun>>> {
-        let gun1 = self.make_gun(gun, std::f64::consts::PI / 8.0);
-        let gun2 = self.make_gun(gun, -(std::f64::consts::PI / 8.0));
+        (0..self.definition.spawn_count).map(|gun_index| Rc::new(RefCell::new(self.make_gun(gun, self.spread_offset(gun_index))))).collect()
+    }
+}
 
-        gun.gun_sound.borrow_mut().play();
+impl GenericGunBehavior {
+    // Guns fan out from one edge of the spread to the other.
+    fn spread_offset(&self, gun_index: usize) -> f64 {
+        if self.definition.spawn_count < 2 {
+            return 0.0;
+        }
 
-        vec![
-            Rc::new(RefCell::new(gun1)),
-            Rc::new(RefCell::new(gun2))
-        ]
+        let spread = self.definition.spread_degrees.to_radians();
+        spread * 0.5 - spread * gun_index as f64 / (self.definition.spawn_count - 1) as f64
     }
-}
 
-impl ShotGunBehavior {
+    // New guns appear along their spread angle but all fly the way the old gun was facing.
     fn make_gun(&self, gun: &Gun, rotation_offset: f64) -> Gun {
         let old_gun_rotation = gun.get_rotation();
 
@@ -69,20 +87,20 @@ impl ShotGunBehavior {
             x: angle_of_position_offset.cos(),
             y: angle_of_position_offset.sin(),
         };
-        let new_gun_position = *gun.get_position() + unit_position_offset * 75.0;
+        let new_gun_position = *gun.get_position() + unit_position_offset * self.definition.spawn_offset;
 
-        let velocity_angle = old_gun_rotation;
         let unit_velocity = Vector2 {
-            x: velocity_angle.cos(),
-            y: velocity_angle.sin(),
+            x: old_gun_rotation.cos(),
+            y: old_gun_rotation.sin(),
         };
-        let new_gun_velocity = unit_velocity * PROJECTILE_VELOCITY_MAGNITUDE;
-
+        let new_gun_velocity = unit_velocity * self.definition.projectile_speed;
 
         Gun {
             position: new_gun_position,
             rotation: old_gun_rotation,
-            scale: GUN_SCALE,
+            previous_position: new_gun_position,
+            previous_rotation: old_gun_rotation,
+            scale: gun.scale,
             renderable_object: RenderableObject {
                 texture: gun.gun_texture.clone(),
             },
@@ -93,8 +111,8 @@ impl ShotGunBehavior {
             collidable_object: CollidableObject {
                 width: f64::from(gun.gun_texture.get_size().0),
                 height: f64::from(gun.gun_texture.get_size().1),
+                shape: gun.collidable_object.shape.clone(),
             },
-            gun_sound: gun.gun_sound.clone(),
             gun_texture: gun.gun_texture.clone(),
             selected_gun_texture: gun.selected_gun_texture.clone(),
             gun_behavior: gun.gun_behavior.new_gun_behavior(),
@@ -1,73 +1,101 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use ears::AudioController;
-use ears::Sound;
-use piston_window::G2dTexture;
 use piston_window::ImageSize;
 
 use crate::bullet::Bullet;
+use crate::bullet_behavior::BulletBehavior;
 use crate::collidable_object::CollidableObject;
 use crate::game_object::GameObject;
 use crate::gun::Gun;
-use crate::gun::GUN_SCALE;
-use crate::gun::PROJECTILE_VELOCITY_MAGNITUDE;
+use crate::config::BulletSource;
+use crate::config::ColliderShape;
+use crate::config::GunDefinition;
+use crate::config::TriggerMode;
 use crate::gun_concept::GunConcept;
 use crate::gun_behavior::GunBehavior;
 use crate::renderable_object::RenderableObject;
 use crate::vector2::Vector2;
-use crate::world::WorldReq;
-use crate::world::WorldRequestType;
-
-pub struct ShotGunConcept {
-    pub gun_texture: Rc<G2dTexture>,
-    pub gun_image_id: conrod_core::image::Id,
-    pub selected_gun_texture: Rc<G2dTexture>,
-    pub selected_gun_image_id: conrod_core::image::Id,
-    pub gun_sound: Rc<RefCell<Sound>>,
-    pub bullet_texture: Rc<G2dTexture>,
-    pub bullet_image_id: conrod_core::image::Id,
-    pub bullet_sound: Rc<RefCell<Sound>>,
+use crate::simulation::WorldReq;
+use crate::simulation::WorldRequestType;
+use crate::texture_handle::TextureHandle;
+
+// Any gun described by a `GunDefinition`; the definition decides how it's triggered and which guns fire bullets.
+pub struct GenericGunConcept {
+    pub definition: Rc<GunDefinition>,
+    pub gun_scale: f64,
+    pub bullet_scale: f64,
+    pub gun_collider: ColliderShape,
+    pub bullet_collider: ColliderShape,
+    // Made from the gun definition's bullet kind, unless the level overrides it.
+    pub bullet_behavior: Rc<BulletBehavior>,
+    pub bullet_speed: f64,
+    pub gun_texture: TextureHandle,
+    pub selected_gun_texture: TextureHandle,
+    pub gun_sound: String,
+    pub bullet_texture: TextureHandle,
+    pub bullet_sound: String,
     pub gun_behavior: Box<GunBehavior>,
     pub shots_taken: usize, // drinks all around https://www.youtube.com/watch?v=XNtTEibFvlQ
     pub guns: Vec<Rc<RefCell<Gun>>>,
-    pub has_shot_bullet: bool,
+    pub bullets_shot: usize,
+    pub bullet_count: usize,
     pub is_selected: bool,
 }
 
-
-impl ShotGunConcept {
-    fn select_mah_guns(&mut self) {
-        let deepest_gun_depth = if let Some(last_gun) = self.guns.last() {
-            last_gun.borrow().depth
-        } else {
-            0
+impl GenericGunConcept {
+    // The guns from the latest shot. They're always at the end of `guns`.
+    fn newest_guns(&self) -> Vec<Rc<RefCell<Gun>>> {
+        let newest_gun_depth = match self.guns.last() {
+            Some(last_gun) => last_gun.borrow().depth,
+            None => return Vec::new(),
         };
 
+        self.guns.iter().rev().take_while(|gun| gun.borrow().depth == newest_gun_depth).cloned().collect()
+    }
+
+    fn select_newest_guns(&mut self) {
         for gun in &self.guns {
-            let is_selected = gun.borrow().depth == deepest_gun_depth;
-            gun.borrow_mut().is_selected = is_selected;
+            gun.borrow_mut().is_selected = false;
+        }
+        for gun in self.newest_guns() {
+            gun.borrow_mut().is_selected = self.is_selected;
         }
     }
+
+    fn shoot_guns(&mut self, player_pos: &Vector2, player_rot: f64, mouse_pos: &Vector2) -> Vec<WorldReq> {
+        if !self.can_shoot_gun() {
+            return Vec::new();
+        }
+
+        let new_guns = if self.guns.is_empty() {
+            self.shoot_gun_from_player(player_pos, player_rot, mouse_pos)
+        } else {
+            let mut shot_guns: Vec<Rc<RefCell<Gun>>> = vec![];
+            for gun in self.newest_guns() {
+                gun.borrow_mut().is_selected = false;
+                shot_guns.append(&mut gun.borrow().shoot_gun());
+            }
+            shot_guns
+        };
+
+        self.guns.append(&mut new_guns.clone());
+        self.shots_taken += 1;
+        self.world_requests_for_guns(new_guns)
+    }
 }
 
-impl GunConcept for ShotGunConcept {
-    fn gun_texture(&self) -> &Rc<G2dTexture> {
+impl GunConcept for GenericGunConcept {
+    fn gun_texture(&self) -> &TextureHandle {
         &self.gun_texture
     }
-    fn gun_image_id(&self) -> conrod_core::image::Id {
-        self.gun_image_id
-    }
-    fn selected_gun_texture(&self) -> &Rc<G2dTexture> {
+    fn selected_gun_texture(&self) -> &TextureHandle {
         &self.selected_gun_texture
     }
-    fn selected_gun_image_id(&self) -> conrod_core::image::Id {
-        self.selected_gun_image_id
-    }
-    fn gun_sound(&self) -> &Rc<RefCell<Sound>> {
+    fn gun_sound(&self) -> &str {
         &self.gun_sound
     }
-    fn bullet_sound(&self) -> &Rc<RefCell<Sound>> {
+    fn bullet_sound(&self) -> &str {
         &self.bullet_sound
     }
     fn gun_behavior(&self) -> &GunBehavior {
@@ -77,7 +105,13 @@ impl GunConcept for ShotGunConcept {
         &self.guns
     }
     fn has_shot_bullet(&self) -> bool {
-        self.has_shot_bullet
+        self.bullets_shot > 0
+    }
+    fn bullets_shot(&self) -> usize {
+        self.bullets_shot
+    }
+    fn bullet_count(&self) -> usize {
+        self.bullet_count
     }
     fn is_selected(&self) -> bool {
         self.is_selected
@@ -86,12 +120,12 @@ impl GunConcept for ShotGunConcept {
     fn shots_taken(&self) -> usize {
         self.shots_taken
     }
-    fn bullet_image_id(&self) -> conrod_core::image::Id {
-        self.bullet_image_id
-    }
-    fn bullet_texture(&self) -> &Rc<G2dTexture> {
+    fn bullet_texture(&self) -> &TextureHandle {
         &self.bullet_texture
     }
+    fn bullet_behavior(&self) -> &BulletBehavior {
+        self.bullet_behavior.as_ref()
+    }
 
     fn has_guns_in_play(&self) -> bool {
         !self.guns.is_empty()
@@ -111,20 +145,16 @@ impl GunConcept for ShotGunConcept {
 
     fn set_selected(&mut self, selected: bool) {
         self.is_selected = selected;
+        self.select_newest_guns();
     }
 
     fn update(&mut self) {
         self.guns.retain(|ref gun| !gun.borrow().get_should_delete());
-
-        if !self.is_selected {
-            return;
-        }
-
-        self.select_mah_guns();
+        self.select_newest_guns();
     }
 
     fn can_shoot_bullet(&self) -> bool {
-        if self.has_shot_bullet {
+        if self.bullets_shot >= self.bullet_count {
             return false;
         }
 
@@ -136,7 +166,7 @@ impl GunConcept for ShotGunConcept {
     }
 
     fn can_shoot_gun(&self) -> bool {
-        if self.has_shot_bullet {
+        if self.has_shot_bullet() {
             return false;
         }
 
@@ -148,44 +178,30 @@ impl GunConcept for ShotGunConcept {
     }
 
     fn gun_trigger_pressed(&mut self, player_pos: &Vector2, player_rot: f64, mouse_pos: &Vector2) -> Vec<WorldReq> {
-        if !self.can_shoot_gun() {
+        if self.definition.trigger != TriggerMode::Press {
             return Vec::new();
         }
 
-        let new_guns = if self.guns.is_empty() {
-            self.shoot_gun_from_player(player_pos, player_rot, mouse_pos)
-        } else {
-            let mut shot_guns: Vec<Rc<RefCell<Gun>>> = vec![]; // I see what you did there.
-            let deepest_gun_depth = if let Some(last_gun) = self.guns.last() {
-                last_gun.borrow().depth
-            } else {
-                0
-            };
-            for gun in self.guns.iter().rev() {
-                if gun.borrow().depth != deepest_gun_depth {
-                    break;
-                }
-                shot_guns.append(&mut gun.borrow().shoot_gun());
-            }
-            shot_guns
-        };
-
-        self.guns.append(&mut new_guns.clone());
-        self.shots_taken += 1;
-        self.world_requests_for_guns(new_guns)
+        self.shoot_guns(player_pos, player_rot, mouse_pos)
     }
 
-    fn gun_trigger_held(&mut self, _player_pos: &Vector2, _player_rot: f64, _mouse_pos: &Vector2) -> Vec<WorldReq> {
-        Vec::new()
+    fn gun_trigger_held(&mut self, player_pos: &Vector2, player_rot: f64, mouse_pos: &Vector2) -> Vec<WorldReq> {
+        if self.definition.trigger != TriggerMode::Held {
+            return Vec::new();
+        }
+
+        self.shoot_guns(player_pos, player_rot, mouse_pos)
     }
 
     fn shoot_gun_from_player(&mut self, player_pos: &Vector2, player_rot: f64, mouse_pos: &Vector2) -> Vec<Rc<RefCell<Gun>>> {
-        let velocity = (*mouse_pos - *player_pos).normalized() * PROJECTILE_VELOCITY_MAGNITUDE;
+        let velocity = (*mouse_pos - *player_pos).normalized() * self.definition.projectile_speed;
 
         let gun = Gun {
             position: *player_pos,
             rotation: player_rot,
-            scale: GUN_SCALE,
+            previous_position: *player_pos,
+            previous_rotation: player_rot,
+            scale: self.gun_scale,
             renderable_object: RenderableObject {
                 texture: self.gun_texture.clone(),
             },
@@ -196,8 +212,8 @@ impl GunConcept for ShotGunConcept {
             collidable_object: CollidableObject {
                 width: f64::from(self.gun_texture.get_size().0),
                 height: f64::from(self.gun_texture.get_size().1),
+                shape: self.gun_collider.clone(),
             },
-            gun_sound: self.gun_sound.clone(),
             gun_texture: self.gun_texture.clone(),
             selected_gun_texture: self.selected_gun_texture.clone(),
             gun_behavior: self.new_gun_behavior(),
@@ -206,8 +222,6 @@ impl GunConcept for ShotGunConcept {
             is_visible: true,
         };
 
-        self.gun_sound.borrow_mut().play();
-
         vec![Rc::new(RefCell::new(gun))]
     }
 
@@ -217,6 +231,10 @@ impl GunConcept for ShotGunConcept {
 
         let mut world_reqs: Vec<WorldReq> = vec![];
 
+        if !guns.is_empty() {
+            world_reqs.push(WorldReq::play_sound(&self.gun_sound));
+        }
+
         for gun in guns {
             self.world_requests_for_gun(gun, &mut world_reqs);
         }
@@ -229,6 +247,8 @@ impl GunConcept for ShotGunConcept {
             renderable: Some(gun.clone()),
             updatable: None,
             collidable: Some(gun.clone()),
+            sound: None,
+            explosion: None,
             req_type: WorldRequestType::AddDynamicRenderable,
         };
         world_reqs.push(world_req);
@@ -236,6 +256,8 @@ impl GunConcept for ShotGunConcept {
             renderable: None,
             updatable: Some(gun.clone()),
             collidable: None,
+            sound: None,
+            explosion: None,
             req_type: WorldRequestType::AddUpdatable,
         };
         world_reqs.push(world_req);
@@ -248,46 +270,24 @@ impl GunConcept for ShotGunConcept {
 
         let mut world_reqs: Vec<WorldReq> = Vec::new();
 
-        let deepest_gun_depth = if let Some(last_gun) = self.guns.last() {
-            last_gun.borrow().depth
-        } else {
-            0
+        let firing_guns = match self.definition.bullets_from {
+            BulletSource::EveryGun => self.guns.clone(),
+            BulletSource::NewestGuns => self.newest_guns(),
         };
-        for gun in self.guns.iter().rev() {
-            if gun.borrow().depth != deepest_gun_depth {
-                break;
-            }
-            let bullet = Rc::new(RefCell::new(gun.borrow_mut().shoot_bullet(&self.bullet_texture)));
-            self.bullet_sound.borrow_mut().play();
+        for gun in &firing_guns {
+            let bullet = Rc::new(RefCell::new(gun.borrow_mut().shoot_bullet(&self.bullet_texture, &self.bullet_collider, &self.bullet_behavior, self.bullet_scale, self.bullet_speed)));
             world_reqs.append(&mut self.world_requests_for_bullet(bullet));
         }
 
-        self.has_shot_bullet = true;
+        world_reqs.push(WorldReq::play_sound(&self.bullet_sound));
+        self.bullets_shot += 1;
 
         world_reqs
     }
 
     // TODO: DUPLICATES world_requests_for_gun
     fn world_requests_for_bullet(&self, bullet: Rc<RefCell<Bullet>>) -> Vec<WorldReq> {
-        // TODO: https://stackoverflow.com/questions/28632968/why-doesnt-rust-support-trait-object-upcasting
-
-        let mut world_reqs: Vec<WorldReq> = vec![];
-
-        let world_req: WorldReq = WorldReq {
-            renderable: Some(bullet.clone()),
-            updatable: None,
-            collidable: Some(bullet.clone()),
-            req_type: WorldRequestType::AddDynamicRenderable,
-        };
-        world_reqs.push(world_req);
-        let world_req: WorldReq = WorldReq {
-            renderable: None,
-            updatable: Some(bullet.clone()),
-            collidable: None,
-            req_type: WorldRequestType::AddUpdatable,
-        };
-        world_reqs.push(world_req);
-        world_reqs
+        Bullet::world_requests(bullet)
     }
 }
 
@@ -1,6 +1,9 @@
 use crate::bullet::Bullet;
+use crate::bullet_behavior::BulletBehavior;
 use crate::collidable::Collidable;
+use crate::collidable::Collision;
 use crate::collidable_object::CollidableObject;
+use crate::config::ColliderShape;
 use crate::game_object::GameObject;
 use crate::gun_behavior::GunBehavior;
 use crate::input;
@@ -9,9 +12,8 @@ use crate::renderable::Renderable;
 use crate::renderable_object::RenderableObject;
 use crate::updatable::Updatable;
 use crate::vector2::Vector2;
-use crate::world::WorldReq;
-use ears::*;
-use piston_window::G2dTexture;
+use crate::simulation::WorldReq;
+use crate::texture_handle::TextureHandle;
 use piston_window::ImageSize;
 use piston_window::Key;
 use piston_window::MouseButton;
@@ -20,24 +22,18 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-pub const PROJECTILE_VELOCITY_MAGNITUDE: f64 = 75.0;
-pub const GUN_SCALE: f64 = 0.5;
-
-pub const BULLET_VELOCITY_MAGNITUDE: f64 = 200.0;
-pub const BULLET_SCALE: f64 = 0.03125;
-pub const GUN_ROTATIONAL_VELOCITY: f64 = 4.0;
-
 pub struct Gun {
     pub position: Vector2,
     pub rotation: f64,
+    pub previous_position: Vector2,
+    pub previous_rotation: f64,
     pub scale: f64,
     pub renderable_object: RenderableObject,
     pub selected_renderable_object: RenderableObject,
     pub collidable_object: CollidableObject,
     pub velocity: Vector2,
-    pub gun_texture: Rc<G2dTexture>,
-    pub selected_gun_texture: Rc<G2dTexture>,
-    pub gun_sound: Rc<RefCell<Sound>>,
+    pub gun_texture: TextureHandle,
+    pub selected_gun_texture: TextureHandle,
     pub gun_behavior: Box<GunBehavior>,
     pub is_selected: bool,
     pub depth: u32,
@@ -65,6 +61,14 @@ impl GameObject for Gun {
         self.gun_behavior.set_should_delete(should_delete)
     }
 
+    fn get_previous_position(&self) -> &Vector2 {
+        &self.previous_position
+    }
+
+    fn get_previous_rotation(&self) -> f64 {
+        self.previous_rotation
+    }
+
     fn get_object_type(&self) -> ObjectType {
         self.gun_behavior.get_object_type()
     }
@@ -87,8 +91,10 @@ impl Renderable for Gun {
 impl Updatable for Gun {
     #[allow(unused_variables)]
     fn update(&mut self, key_states: &HashMap<Key, input::ButtonState>, mouse_states: &HashMap<MouseButton, input::ButtonState>, mouse_pos: &Vector2, args: UpdateArgs) -> Vec<WorldReq> {
+        self.previous_position = self.position;
+        self.previous_rotation = self.rotation;
         self.position += self.velocity * args.dt;
-        self.rotation += GUN_ROTATIONAL_VELOCITY * args.dt;
+        self.rotation += self.gun_behavior.spin_rate() * args.dt;
         Vec::new()
     }
 }
@@ -98,13 +104,29 @@ impl Collidable for Gun {
         &self.collidable_object
     }
 
-    fn collide(&mut self, other_object_type: ObjectType) {
-        self.gun_behavior.collide(other_object_type)
+    fn get_velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    fn set_velocity(&mut self, velocity: Vector2) {
+        self.velocity = velocity
+    }
+
+    fn set_position(&mut self, position: Vector2) {
+        self.position = position
+    }
+
+    fn collide(&mut self, _collision: &Collision) -> Vec<WorldReq> {
+        Vec::new()
+    }
+
+    fn kind_names(&self) -> Vec<String> {
+        vec![String::from(self.get_object_type().name()), String::from(self.gun_behavior.name())]
     }
 }
 
 impl Gun {
-    pub fn shoot_bullet(&self, bullet_texture: &Rc<G2dTexture>) -> Bullet {
+    pub fn shoot_bullet(&self, bullet_texture: &TextureHandle, bullet_collider: &ColliderShape, bullet_behavior: &Rc<BulletBehavior>, bullet_scale: f64, bullet_speed: f64) -> Bullet {
         let velocity = Vector2 {
             x: self.rotation.cos(),
             y: self.rotation.sin(),
@@ -113,17 +135,24 @@ impl Gun {
         Bullet {
             position: self.position,
             rotation: self.rotation,
-            scale: BULLET_SCALE,
+            previous_position: self.position,
+            previous_rotation: self.rotation,
+            scale: bullet_scale * bullet_behavior.scale_multiplier(),
             renderable_object: RenderableObject {
                 texture: bullet_texture.clone(),
             },
-            velocity: velocity * BULLET_VELOCITY_MAGNITUDE,
+            velocity: velocity * bullet_speed * bullet_behavior.speed_multiplier(),
             should_delete: false,
             collidable_object: CollidableObject {
                 width: f64::from(bullet_texture.get_size().0),
                 height: f64::from(bullet_texture.get_size().1),
+                shape: bullet_collider.clone(),
             },
             is_visible: true,
+            object_type: ObjectType::Bullet,
+            bullet_behavior: bullet_behavior.clone(),
+            bounces: 0,
+            pierced: Vec::new(),
         }
     }
 
@@ -4,12 +4,14 @@ use std::rc::Rc;
 use std::cell::RefCell;
 
 pub trait GunBehavior {
+    // The gun definition's name.
+    fn name(&self) -> &str;
     fn get_should_delete(&self) -> bool;
     fn set_should_delete(&mut self, should_delete: bool);
     fn get_object_type(&self) -> ObjectType;
-    fn collide(&mut self, other_object_type: ObjectType);
     fn new_gun_behavior(&self) -> Box<GunBehavior>;
     fn has_gun_depth(&self) -> bool;
     fn get_gun_depth(&self) -> usize;
+    fn spin_rate(&self) -> f64;
     fn shoot_gun(&self, gun: &Gun) -> Vec<Rc<RefCell<Gun>>>;
 }
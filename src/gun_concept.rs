@@ -2,28 +2,28 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::bullet::Bullet;
+use crate::bullet_behavior::BulletBehavior;
 use crate::gun::Gun;
 use crate::gun_behavior::GunBehavior;
+use crate::simulation::WorldReq;
+use crate::texture_handle::TextureHandle;
 use crate::vector2::Vector2;
-use crate::world::WorldReq;
-use piston_window::G2dTexture;
-use ears::Sound;
 
 pub trait GunConcept {
-    fn gun_texture(&self) -> &Rc<G2dTexture>;
-    fn gun_image_id(&self) -> conrod_core::image::Id;
-    fn selected_gun_texture(&self) -> &Rc<G2dTexture>;
-    fn selected_gun_image_id(&self) -> conrod_core::image::Id;
-    fn gun_sound(&self) -> &Rc<RefCell<Sound>>;
-    fn bullet_sound(&self) -> &Rc<RefCell<Sound>>;
+    fn gun_texture(&self) -> &TextureHandle;
+    fn selected_gun_texture(&self) -> &TextureHandle;
+    fn gun_sound(&self) -> &str;
+    fn bullet_sound(&self) -> &str;
     fn gun_behavior(&self) -> &GunBehavior;
     fn guns(&self) -> &Vec<Rc<RefCell<Gun>>>;
     fn has_shot_bullet(&self) -> bool;
+    fn bullets_shot(&self) -> usize;
+    fn bullet_count(&self) -> usize;
     fn is_selected(&self) -> bool;
 
     fn shots_taken(&self) -> usize;
-    fn bullet_image_id(&self) -> conrod_core::image::Id;
-    fn bullet_texture(&self) -> &Rc<G2dTexture>;
+    fn bullet_texture(&self) -> &TextureHandle;
+    fn bullet_behavior(&self) -> &BulletBehavior;
 
     fn has_guns_in_play(&self) -> bool;
     fn has_gun_depth(&self) -> bool;
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use piston_window::Key;
+use piston_window::MouseButton;
+use piston_window::UpdateArgs;
+
+use crate::config;
+use crate::config::Config;
+use crate::error::Error;
+use crate::headless_texture_registry::HeadlessTextureRegistry;
+use crate::input;
+use crate::level_loader;
+use crate::replay::Replay;
+use crate::simulation::GameEndedState;
+use crate::simulation::Simulation;
+use crate::vector2::Vector2;
+use crate::vfs::Vfs;
+
+// Loads a level the same way the game does, minus the window and the GPU.
+pub fn make_simulation(level_name: &str, config: &Config) -> Result<Simulation, Error> {
+    let vfs = Rc::new(Vfs::from_config(config)?);
+    let mut texture_registry = HeadlessTextureRegistry {
+        vfs: Rc::clone(&vfs),
+        sizes_by_filename: HashMap::new(),
+    };
+
+    level_loader::load_level(&vfs, &mut texture_registry, level_name, config)
+}
+
+pub fn tick_args(config: &Config) -> UpdateArgs {
+    UpdateArgs {
+        dt: 1.0 / f64::from(config.game_loop_config.ticks_per_second),
+    }
+}
+
+// Steps a level with no input. Handy for checking a level on machines without a GPU.
+pub fn run_simulation(level_name: &str, seconds: f64) -> Result<(), Error> {
+    let config = config::read_default_config()?;
+    let mut simulation = make_simulation(level_name, &config)?;
+
+    let key_states: HashMap<Key, input::ButtonState> = HashMap::new();
+    let mouse_states: HashMap<MouseButton, input::ButtonState> = HashMap::new();
+    let mouse_pos = Vector2::default();
+    let args = tick_args(&config);
+
+    let num_ticks = (seconds / args.dt).ceil() as u64;
+    for _ in 0..num_ticks {
+        simulation.update(&key_states, &mouse_states, &mouse_pos, args);
+        simulation.sounds_to_play.clear();
+    }
+
+    let num_objects: usize = simulation.renderables.iter().map(|renderable_layer| renderable_layer.len()).sum();
+    println!("Simulated {} for {} ticks ({} objects).", level_name, num_ticks, num_objects);
+    println!("Result: {}", describe_game_ended_state(&simulation.game_ended_state));
+    Ok(())
+}
+
+// Plays a replay back as fast as possible and reports how the level ended.
+pub fn run_replay(replay: &Replay) -> Result<(), Error> {
+    let mut simulation = make_simulation(&replay.level_name, &replay.config)?;
+    let args = tick_args(&replay.config);
+
+    for tick in &replay.ticks {
+        simulation.update(&tick.key_states, &tick.mouse_states, &tick.mouse_pos, args);
+        simulation.sounds_to_play.clear();
+    }
+
+    println!("Replayed {} for {} ticks.", replay.level_name, replay.ticks.len());
+    println!("Result: {}", describe_game_ended_state(&simulation.game_ended_state));
+    Ok(())
+}
+
+pub fn describe_game_ended_state(game_ended_state: &GameEndedState) -> &'static str {
+    if !game_ended_state.game_ended {
+        "still running"
+    } else if game_ended_state.won {
+        "victorious"
+    } else {
+        "defeated"
+    }
+}
@@ -0,0 +1,34 @@
+use image::GenericImageView;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::error::Error;
+use crate::texture_handle::TextureHandle;
+use crate::texture_registry::TextureRegistry;
+use crate::vfs::Vfs;
+
+// Reads texture sizes straight from the image files so levels can be loaded without a window or GPU.
+pub struct HeadlessTextureRegistry {
+    pub vfs: Rc<Vfs>,
+    pub sizes_by_filename: HashMap<String, (u32, u32)>,
+}
+
+impl TextureRegistry for HeadlessTextureRegistry {
+    fn get_handle(&mut self, texture_name: &str) -> Result<TextureHandle, Error> {
+        let (width, height) = match self.sizes_by_filename.get(texture_name) {
+            Some(&size) => size,
+            None => {
+                let bytes = self.vfs.read(texture_name).map_err(|err| Error::missing_asset(texture_name, &err))?;
+                let size = image::load_from_memory(&bytes).map_err(|err| Error::bad_asset(texture_name, &err))?.dimensions();
+                self.sizes_by_filename.insert(texture_name.to_string(), size);
+                size
+            }
+        };
+
+        Ok(TextureHandle {
+            name: texture_name.to_string(),
+            width,
+            height,
+        })
+    }
+}
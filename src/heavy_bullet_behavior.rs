@@ -0,0 +1,21 @@
+use crate::bullet_behavior::BulletBehavior;
+
+// Slow and big, so it's harder to squeeze past things with.
+pub struct HeavyBulletBehavior {
+    pub speed_multiplier: f64,
+    pub scale_multiplier: f64,
+}
+
+impl BulletBehavior for HeavyBulletBehavior {
+    fn name(&self) -> &str {
+        "heavy"
+    }
+
+    fn speed_multiplier(&self) -> f64 {
+        self.speed_multiplier
+    }
+
+    fn scale_multiplier(&self) -> f64 {
+        self.scale_multiplier
+    }
+}
@@ -0,0 +1,86 @@
+use crate::collidable::Collidable;
+use crate::collidable::Collision;
+use crate::collidable_object::CollidableObject;
+use crate::game_object::GameObject;
+use crate::object_type::ObjectType;
+use crate::renderable::Renderable;
+use crate::renderable_object::RenderableObject;
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
+
+// Someone who mustn't be shot. Hitting one loses the level, so they don't have to be cleared to win.
+pub struct Hostage {
+    pub position: Vector2,
+    pub rotation: f64,
+    pub scale: f64,
+    pub renderable_object: RenderableObject,
+    pub collidable_object: CollidableObject,
+    pub is_visible: bool,
+    pub should_delete: bool,
+    pub was_hit: bool,
+}
+
+impl GameObject for Hostage {
+    fn get_position(&self) -> &Vector2 {
+        &self.position
+    }
+
+    fn get_rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    fn get_scale(&self) -> f64 {
+        self.scale
+    }
+
+    fn get_should_delete(&self) -> bool {
+        self.should_delete
+    }
+
+    fn set_should_delete(&mut self, should_delete: bool) {
+        self.should_delete = should_delete;
+    }
+
+    fn get_object_type(&self) -> ObjectType {
+        ObjectType::Hostage
+    }
+}
+
+impl Renderable for Hostage {
+    fn get_renderable_object(&self) -> &RenderableObject {
+        &self.renderable_object
+    }
+
+    fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+}
+
+impl Collidable for Hostage {
+    fn get_collidable_object(&self) -> &CollidableObject {
+        &self.collidable_object
+    }
+
+    fn get_velocity(&self) -> Vector2 {
+        Vector2::default()
+    }
+
+    fn set_velocity(&mut self, _velocity: Vector2) {}
+
+    fn set_position(&mut self, _position: Vector2) {}
+
+    fn collide(&mut self, _collision: &Collision) -> Vec<WorldReq> {
+        Vec::new()
+    }
+
+    // Whatever would destroy a hostage, a bullet, a lethal gun or a blast, loses the level instead. It stays where it
+    // is, so you can see who you shot.
+    fn survives_hit(&mut self, _direction_to_hitter: Vector2, _hitter_kind_names: &[String]) -> bool {
+        self.was_hit = true;
+        true
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
@@ -1,11 +1,13 @@
+use crate::serde::{Deserialize, Serialize};
 use piston_window::*;
 use std::collections::HashMap;
+use std::hash::Hash;
 
 use crate::vector2::*;
 
 // TODO: Make singleton.
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct ButtonState {
     // "Pressed" for 2+ frames.
     pub held: bool,
@@ -106,3 +108,18 @@ pub fn update_input(key_states: &mut HashMap<Key, ButtonState>, mouse_states: &m
         }
     }
 }
+
+// Folds one frame's button states into the states the next tick will see. A press or release
+// that no tick has consumed yet is kept, so quick clicks aren't lost on frames that run zero ticks.
+pub fn merge_input<T: Copy + Eq + Hash>(pending_states: &mut HashMap<T, ButtonState>, frame_states: &HashMap<T, ButtonState>) {
+    for (button, frame_state) in frame_states {
+        let has_unconsumed_edge = match pending_states.get(button) {
+            Some(pending_state) => pending_state.pressed || pending_state.released,
+            None => false,
+        };
+
+        if frame_state.pressed || frame_state.released || !has_unconsumed_edge {
+            pending_states.insert(*button, *frame_state);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use crate::serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use csv;
+
+use crate::config::BulletKind;
+use crate::config::TuningOverrides;
+use crate::error::Error;
+use crate::vfs;
+use crate::vfs::Vfs;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LevelGunConcept {
+    // The name of one of the config's `gun_definitions`.
+    pub kind: String,
+    // How many guns may be chained before the bullets have to fire. Leave it out for the gun's default.
+    #[serde(default)]
+    pub gun_depth: Option<usize>,
+    // How many times the bullets can be fired. Once they have been, no more guns can be shot.
+    #[serde(default = "default_bullet_count")]
+    pub bullets: usize,
+    // What the bullets do, e.g. `{ "kind": "ricochet", "max_bounces": 3, "speed_loss_per_bounce": 0.2 }`. Leave it out
+    // for the gun's default.
+    #[serde(default)]
+    pub bullet_kind: Option<BulletKind>,
+}
+
+fn default_bullet_count() -> usize {
+    1
+}
+
+// A cell of the tile grid, counted from 1 like the linter does.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelCell {
+    pub row: usize,
+    pub column: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EnemyBehaviorKind {
+    // Stays put, like every enemy used to.
+    Stationary,
+    // Walks from cell to cell along `path`, then back to the first and round again.
+    Patrol { path: Vec<LevelCell> },
+    // Heads straight for the player.
+    Chase,
+    // Stays put and shoots at the player every so often.
+    Turret,
+}
+
+// Not derived: `#[default]` on a variant needs a newer rustc than our pinned dependencies build with.
+#[allow(clippy::derivable_impls)]
+impl Default for EnemyBehaviorKind {
+    fn default() -> Self {
+        EnemyBehaviorKind::Stationary
+    }
+}
+
+// Gives the enemy in one cell something to do, or points it somewhere.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LevelEnemy {
+    pub row: usize,
+    pub column: usize,
+    #[serde(default)]
+    pub behavior: EnemyBehaviorKind,
+    // Which way it starts out facing, clockwise from the right. Shields block hits from this side.
+    #[serde(default)]
+    pub facing_degrees: f64,
+}
+
+// Ties a switch to the doors it opens and closes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LevelSwitch {
+    pub row: usize,
+    pub column: usize,
+    pub doors: Vec<LevelCell>,
+}
+
+// Everything a level defines: what it's called, which guns the player gets and the tile grid.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LevelDocument {
+    pub title: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    // The number of gun shots the level was designed to be beaten in.
+    #[serde(default)]
+    pub par: Option<usize>,
+    // In the order they show up on the HUD and cycle with Q and E.
+    pub gun_concepts: Vec<LevelGunConcept>,
+    // One string per row, one character per cell: W wall, B breakable wall, G glass wall, R bumper wall, P player,
+    // C hostage, L switch, D closed door, O open door, _ ground, or one of the config's enemy variants, like E for a
+    // plain enemy.
+    pub tiles: Vec<String>,
+    // Enemies not listed here are stationary and face right.
+    #[serde(default)]
+    pub enemies: Vec<LevelEnemy>,
+    #[serde(default)]
+    pub switches: Vec<LevelSwitch>,
+    #[serde(default)]
+    pub tuning: TuningOverrides,
+}
+
+// Every level shipped as a bare CSV gets the same four guns.
+const LEGACY_GUN_CONCEPTS: [&str; 4] = ["hand_gun", "gun_axe", "tommy_gun", "shot_gun"];
+
+// Reads `Levels/<level_name>.json` from the mounted assets, falling back to the legacy `<level_name>.csv`.
+// `level_name` can also be the path to a level file, which is how custom levels are named.
+pub fn read_level_document(vfs: &Vfs, level_name: &str) -> Result<LevelDocument, Error> {
+    try_read_level_document(vfs, &level_path(vfs, level_name))
+}
+
+// Either a path on disk or an asset path.
+pub fn level_path(vfs: &Vfs, level_name: &str) -> String {
+    if Path::new(level_name).is_file() {
+        return String::from(level_name);
+    }
+
+    let json_asset_path = format!("Levels/{}.json", vfs::normalize_asset_path(level_name));
+    if vfs.exists(&json_asset_path) {
+        return json_asset_path;
+    }
+
+    format!("Levels/{}.csv", vfs::normalize_asset_path(level_name))
+}
+
+// Reads a level from a `.json` or legacy `.csv` file, on disk if there is one at `path` and from the mounted assets otherwise.
+pub fn try_read_level_document(vfs: &Vfs, path: &str) -> Result<LevelDocument, Error> {
+    let bytes = if Path::new(path).is_file() { fs::read(path) } else { vfs.read(path) };
+    let bytes = bytes.map_err(|err| Error::missing_asset(path, &err))?;
+
+    if path.ends_with(".csv") {
+        return read_level_document_from_csv(path, &bytes);
+    }
+
+    serde_json::from_slice(&bytes).map_err(|err| {
+        // serde_json tacks the position onto its messages; we show it ourselves.
+        let position_suffix = format!(" at line {} column {}", err.line(), err.column());
+        Error::LevelParse {
+            path: String::from(path),
+            line: Some(err.line()),
+            column: Some(err.column()),
+            message: err.to_string().trim_end_matches(&position_suffix).to_string(),
+        }
+    })
+}
+
+fn read_level_document_from_csv(path: &str, bytes: &[u8]) -> Result<LevelDocument, Error> {
+    let mut csv_rdr = csv::ReaderBuilder::new().has_headers(false).flexible(true).from_reader(bytes);
+
+    let mut tiles = Vec::new();
+    for record_result in csv_rdr.records() {
+        let line = record_result.map_err(|err| Error::LevelParse {
+            path: String::from(path),
+            line: csv_error_line(&err),
+            column: None,
+            message: err.to_string(),
+        })?;
+        let mut row = String::new();
+        for (item_num, item) in line.iter().enumerate() {
+            if item.chars().count() != 1 {
+                return Err(Error::LevelParse {
+                    path: String::from(path),
+                    line: line.position().map(|position| position.line() as usize),
+                    column: Some(item_num + 1),
+                    message: format!("cell {:?} should be a single character", item),
+                });
+            }
+            row.push(item.chars().next().unwrap());
+        }
+        tiles.push(row);
+    }
+
+    let level_name = Path::new(path).file_stem().map_or_else(String::new, |file_stem| file_stem.to_string_lossy().into_owned());
+    Ok(LevelDocument {
+        title: level_name,
+        author: String::new(),
+        description: String::new(),
+        par: None,
+        gun_concepts: LEGACY_GUN_CONCEPTS
+            .iter()
+            .map(|&kind| LevelGunConcept {
+                kind: String::from(kind),
+                gun_depth: None,
+                bullets: default_bullet_count(),
+                bullet_kind: None,
+            })
+            .collect(),
+        tiles,
+        enemies: Vec::new(),
+        switches: Vec::new(),
+        tuning: TuningOverrides::default(),
+    })
+}
+
+fn csv_error_line(err: &csv::Error) -> Option<usize> {
+    match err.kind() {
+        csv::ErrorKind::Utf8 { pos: Some(position), .. } => Some(position.line() as usize),
+        _ => None,
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+
+use crate::config::Config;
+use crate::config::NON_ENEMY_CELLS;
+use crate::level_document;
+use crate::level_document::EnemyBehaviorKind;
+use crate::level_document::LevelDocument;
+use crate::vfs::Vfs;
+
+// Everything wrong with a level that we can tell without playing it. Rows and columns are counted from 1.
+pub fn lint_level(level: &LevelDocument, config: &Config) -> Vec<String> {
+    let tuning = config.tuning_config.with_overrides(&level.tuning);
+    let mut problems: Vec<String> = tuning.validate().into_iter().map(|problem| format!("tuning: {}", problem)).collect();
+
+    if level.tiles.len() != tuning.grid_height as usize {
+        problems.push(format!("has {} rows, expected {}", level.tiles.len(), tuning.grid_height));
+    }
+    for (row_index, row) in level.tiles.iter().enumerate() {
+        let row_width = row.chars().count();
+        if row_width != tuning.grid_width as usize {
+            problems.push(format!("row {} has {} cells, expected {}", row_index + 1, row_width, tuning.grid_width));
+        }
+    }
+
+    let grid: Vec<Vec<char>> = level.tiles.iter().map(|row| row.chars().collect()).collect();
+    let mut spawns = Vec::new();
+    let mut enemies = Vec::new();
+    for (row_index, row) in grid.iter().enumerate() {
+        for (column_index, &tile) in row.iter().enumerate() {
+            match tile {
+                'P' => spawns.push((row_index, column_index)),
+                _ if config.enemy_config.variant(tile).is_some() => enemies.push((row_index, column_index)),
+                _ if !NON_ENEMY_CELLS.contains(&tile) => problems.push(format!("unknown cell {:?} at row {}, column {}", tile, row_index + 1, column_index + 1)),
+                _ => {}
+            }
+        }
+    }
+
+    if spawns.is_empty() {
+        problems.push(String::from("has no player spawn (P)"));
+    } else if spawns.len() > 1 {
+        let positions: Vec<String> = spawns.iter().map(|&(row_index, column_index)| format!("row {}, column {}", row_index + 1, column_index + 1)).collect();
+        problems.push(format!("has {} player spawns (P), at {}", spawns.len(), positions.join("; ")));
+    }
+
+    if enemies.is_empty() {
+        problems.push(String::from("has no enemies"));
+    }
+
+    if let Some(&spawn) = spawns.first() {
+        let reachable = reachable_from(&grid, spawn);
+        if count_open_neighbours(&grid, spawn) == 0 {
+            problems.push(format!("player spawn at row {}, column {} is walled in", spawn.0 + 1, spawn.1 + 1));
+        }
+        for &(row_index, column_index) in &enemies {
+            if !reachable[row_index][column_index] {
+                problems.push(format!("enemy at row {}, column {} is walled off from the player", row_index + 1, column_index + 1));
+            }
+        }
+    }
+
+    for level_enemy in &level.enemies {
+        let tile = level_enemy.row.checked_sub(1).zip(level_enemy.column.checked_sub(1)).and_then(|(row_index, column_index)| grid.get(row_index).and_then(|row| row.get(column_index)));
+        if tile.and_then(|&tile| config.enemy_config.variant(tile)).is_none() {
+            problems.push(format!("enemy behavior at row {}, column {} isn't on an enemy", level_enemy.row, level_enemy.column));
+        }
+        if let EnemyBehaviorKind::Patrol { path } = &level_enemy.behavior {
+            if path.is_empty() {
+                problems.push(format!("patrolling enemy at row {}, column {} has no path", level_enemy.row, level_enemy.column));
+            }
+            for cell in path {
+                let is_open_cell = cell.row > 0 && cell.column > 0 && is_open(&grid, cell.row - 1, cell.column - 1);
+                if !is_open_cell {
+                    problems.push(format!("patrolling enemy at row {}, column {} is sent to row {}, column {}, which is a wall or off the grid", level_enemy.row, level_enemy.column, cell.row, cell.column));
+                }
+            }
+        }
+    }
+
+    let tile_at = |row: usize, column: usize| row.checked_sub(1).zip(column.checked_sub(1)).and_then(|(row_index, column_index)| grid.get(row_index).and_then(|row| row.get(column_index))).cloned();
+    for level_switch in &level.switches {
+        if tile_at(level_switch.row, level_switch.column) != Some('L') {
+            problems.push(format!("switch at row {}, column {} isn't on a switch (L)", level_switch.row, level_switch.column));
+        }
+        for door_cell in &level_switch.doors {
+            let tile = tile_at(door_cell.row, door_cell.column);
+            if tile != Some('D') && tile != Some('O') {
+                problems.push(format!("switch at row {}, column {} opens row {}, column {}, which isn't a door", level_switch.row, level_switch.column, door_cell.row, door_cell.column));
+            }
+        }
+    }
+
+    if level.gun_concepts.is_empty() {
+        problems.push(String::from("doesn't give the player any gun concepts"));
+    }
+    for (gun_concept_index, gun_concept) in level.gun_concepts.iter().enumerate() {
+        if !config.gun_definitions.contains_key(&gun_concept.kind) {
+            problems.push(format!("gun concept {} ({}) isn't in the config's gun_definitions", gun_concept_index + 1, gun_concept.kind));
+        }
+        if gun_concept.gun_depth == Some(0) {
+            problems.push(format!("gun concept {} ({}) has a gun depth of 0, so it can never be shot", gun_concept_index + 1, gun_concept.kind));
+        }
+        if gun_concept.bullets == 0 {
+            problems.push(format!("gun concept {} ({}) has no bullets", gun_concept_index + 1, gun_concept.kind));
+        }
+    }
+
+    problems
+}
+
+// Breakable and glass walls, and doors, can be got through one way or another; plain and bumper walls can't.
+fn is_open(grid: &[Vec<char>], row_index: usize, column_index: usize) -> bool {
+    match grid.get(row_index).and_then(|row| row.get(column_index)) {
+        Some(&tile) => tile != 'W' && tile != 'R',
+        None => false,
+    }
+}
+
+fn neighbours(row_index: usize, column_index: usize) -> Vec<(usize, usize)> {
+    let mut neighbours = vec![(row_index + 1, column_index), (row_index, column_index + 1)];
+    if row_index > 0 {
+        neighbours.push((row_index - 1, column_index));
+    }
+    if column_index > 0 {
+        neighbours.push((row_index, column_index - 1));
+    }
+    neighbours
+}
+
+fn count_open_neighbours(grid: &[Vec<char>], (row_index, column_index): (usize, usize)) -> usize {
+    neighbours(row_index, column_index).into_iter().filter(|&(neighbour_row, neighbour_column)| is_open(grid, neighbour_row, neighbour_column)).count()
+}
+
+// Flood fills every cell a gun could fly to from `start`. Walls touching only at a corner still block
+// the way, since anything squeezing between them touches both.
+fn reachable_from(grid: &[Vec<char>], start: (usize, usize)) -> Vec<Vec<bool>> {
+    let mut reachable: Vec<Vec<bool>> = grid.iter().map(|row| vec![false; row.len()]).collect();
+    let mut to_visit = VecDeque::new();
+    reachable[start.0][start.1] = true;
+    to_visit.push_back(start);
+
+    while let Some((row_index, column_index)) = to_visit.pop_front() {
+        for (neighbour_row, neighbour_column) in neighbours(row_index, column_index) {
+            if is_open(grid, neighbour_row, neighbour_column) && !reachable[neighbour_row][neighbour_column] {
+                reachable[neighbour_row][neighbour_column] = true;
+                to_visit.push_back((neighbour_row, neighbour_column));
+            }
+        }
+    }
+
+    reachable
+}
+
+// Lints a level by name or path. Returns false if anything is wrong with it.
+pub fn run_lint(vfs: &Vfs, config: &Config, level: &str) -> bool {
+    let path = level_document::level_path(vfs, level);
+
+    let problems = match level_document::try_read_level_document(vfs, &path) {
+        Ok(level_document) => lint_level(&level_document, config),
+        Err(err) => vec![err.to_string()],
+    };
+
+    if problems.is_empty() {
+        println!("{}: ok", path);
+        return true;
+    }
+
+    for problem in &problems {
+        println!("{}: {}", path, problem);
+    }
+    false
+}
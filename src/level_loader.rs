@@ -0,0 +1,447 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use piston_window::ImageSize;
+
+use crate::bullet_behavior::BulletBehavior;
+use crate::chase_enemy_behavior::ChaseEnemyBehavior;
+use crate::collidable_object::CollidableObject;
+use crate::config::BulletKind;
+use crate::config::ColliderConfig;
+use crate::config::Config;
+use crate::config::GunDefinition;
+use crate::config::TuningConfig;
+use crate::door::Door;
+use crate::enemy::Enemy;
+use crate::enemy_behavior::EnemyBehavior;
+use crate::error::Error;
+use crate::explosive_bullet_behavior::ExplosiveBulletBehavior;
+use crate::ground::Ground;
+use crate::generic_gun_behavior::GenericGunBehavior;
+use crate::generic_gun_concept::GenericGunConcept;
+use crate::gun_concept::GunConcept;
+use crate::heavy_bullet_behavior::HeavyBulletBehavior;
+use crate::hostage::Hostage;
+use crate::level_document;
+use crate::level_document::EnemyBehaviorKind;
+use crate::level_document::LevelDocument;
+use crate::level_document::LevelGunConcept;
+use crate::object_type::ObjectType;
+use crate::normal_bullet_behavior::NormalBulletBehavior;
+use crate::patrol_enemy_behavior::PatrolEnemyBehavior;
+use crate::piercing_bullet_behavior::PiercingBulletBehavior;
+use crate::player::Player;
+use crate::renderable_object::RenderableObject;
+use crate::ricochet_bullet_behavior::RicochetBulletBehavior;
+use crate::simulation::Simulation;
+use crate::split_bullet_behavior::SplitBulletBehavior;
+use crate::stationary_enemy_behavior::StationaryEnemyBehavior;
+use crate::switch::Switch;
+use crate::texture_handle::TextureHandle;
+use crate::texture_registry::TextureRegistry;
+use crate::turret_enemy_behavior::TurretEnemyBehavior;
+use crate::vector2::*;
+use crate::vfs::Vfs;
+use crate::wall::Wall;
+
+const GROUND_LAYER: usize = 0;
+const WALL_LAYER: usize = 0;
+const ENEMY_LAYER: usize = 1;
+const PLAYER_LAYER: usize = 1;
+
+pub fn load_level(vfs: &Vfs, texture_registry: &mut TextureRegistry, level_name: &str, config: &Config) -> Result<Simulation, Error> {
+    build_level(texture_registry, &level_document::read_level_document(vfs, level_name)?, config)
+}
+
+fn make_bullet_behavior(bullet_kind: &BulletKind) -> Rc<BulletBehavior> {
+    match *bullet_kind {
+        BulletKind::Normal => Rc::new(NormalBulletBehavior),
+        BulletKind::Ricochet { max_bounces, speed_loss_per_bounce } => Rc::new(RicochetBulletBehavior { max_bounces, speed_loss_per_bounce }),
+        BulletKind::Piercing => Rc::new(PiercingBulletBehavior),
+        BulletKind::Explosive { radius } => Rc::new(ExplosiveBulletBehavior { radius }),
+        BulletKind::Heavy { speed_multiplier, scale_multiplier } => Rc::new(HeavyBulletBehavior { speed_multiplier, scale_multiplier }),
+        BulletKind::Split { count, spread_degrees } => Rc::new(SplitBulletBehavior { count, spread_degrees }),
+    }
+}
+
+fn make_enemy_behavior(texture_registry: &mut TextureRegistry, enemy_behavior_kind: &EnemyBehaviorKind, tuning: &TuningConfig, config: &Config) -> Result<Box<EnemyBehavior>, Error> {
+    let enemy_config = &config.enemy_config;
+    Ok(match enemy_behavior_kind {
+        EnemyBehaviorKind::Stationary => Box::new(StationaryEnemyBehavior),
+        EnemyBehaviorKind::Patrol { path } => Box::new(PatrolEnemyBehavior {
+            path: path
+                .iter()
+                .map(|cell| Vector2 {
+                    x: f64::from((cell.column as u32).saturating_sub(1) * tuning.cell_width() + tuning.cell_width() / 2),
+                    y: f64::from((cell.row as u32).saturating_sub(1) * tuning.cell_height() + tuning.cell_height() / 2),
+                })
+                .collect(),
+            speed: enemy_config.patrol_speed,
+            next_waypoint: 0,
+        }),
+        EnemyBehaviorKind::Chase => Box::new(ChaseEnemyBehavior {
+            speed: enemy_config.chase_speed,
+        }),
+        EnemyBehaviorKind::Turret => Box::new(TurretEnemyBehavior {
+            fire_interval: enemy_config.turret_fire_interval,
+            time_until_fire: enemy_config.turret_fire_interval,
+            bullet_texture: texture_registry.get_handle(&enemy_config.turret_bullet_texture)?,
+            bullet_collider: config.collider_config.bullet.clone(),
+            bullet_scale: tuning.bullet_scale,
+            bullet_speed: enemy_config.turret_bullet_speed,
+            bullet_sound: enemy_config.turret_bullet_sound.clone(),
+        }),
+    })
+}
+
+fn make_gun_concept(texture_registry: &mut TextureRegistry, level_gun_concept: &LevelGunConcept, gun_definition: &GunDefinition, tuning: &TuningConfig, colliders: &ColliderConfig) -> Result<Rc<RefCell<GunConcept>>, Error> {
+    let definition = Rc::new(gun_definition.clone());
+
+    Ok(Rc::new(RefCell::new(GenericGunConcept {
+        gun_texture: texture_registry.get_handle(&definition.gun_texture)?,
+        selected_gun_texture: texture_registry.get_handle(&definition.selected_gun_texture)?,
+        gun_sound: definition.gun_sound.clone(),
+        bullet_texture: texture_registry.get_handle(&definition.bullet_texture)?,
+        bullet_sound: definition.bullet_sound.clone(),
+        gun_behavior: Box::new(GenericGunBehavior {
+            name: level_gun_concept.kind.clone(),
+            definition: definition.clone(),
+            gun_depth: level_gun_concept.gun_depth.or(definition.gun_depth),
+            should_delete: false,
+        }),
+        definition,
+        gun_scale: tuning.gun_scale,
+        bullet_scale: tuning.bullet_scale,
+        gun_collider: colliders.gun.clone(),
+        bullet_collider: colliders.bullet.clone(),
+        bullet_behavior: make_bullet_behavior(level_gun_concept.bullet_kind.as_ref().unwrap_or(&gun_definition.bullet_kind)),
+        bullet_speed: tuning.bullet_speed,
+        shots_taken: 0,
+        guns: Vec::new(),
+        bullets_shot: 0,
+        bullet_count: level_gun_concept.bullets,
+        is_selected: false,
+    })))
+}
+
+pub fn build_level(texture_registry: &mut TextureRegistry, level: &LevelDocument, config: &Config) -> Result<Simulation, Error> {
+    let tuning = config.tuning_config.with_overrides(&level.tuning);
+    let problems = tuning.validate();
+    if !problems.is_empty() {
+        return Err(Error::InvalidLevel {
+            level: level.title.clone(),
+            message: format!("overrides the tuning with nonsense: {}", problems.join("; ")),
+        });
+    }
+    let cell_width = tuning.cell_width();
+    let cell_height = tuning.cell_height();
+
+    let hand_gun_texture = texture_registry.get_handle("textures/hand-gun_square.png")?;
+    let selected_hand_gun_texture = texture_registry.get_handle("textures/hand-gun_square_selected.png")?;
+    let wall = texture_registry.get_handle("textures/brick_square.png")?;
+    let ground = texture_registry.get_handle("textures/ground.png")?;
+    let hostage = texture_registry.get_handle("textures/hostage.png")?;
+    let door = texture_registry.get_handle("textures/door.png")?;
+    let switch = texture_registry.get_handle("textures/switch.png")?;
+    let switch_on = texture_registry.get_handle("textures/switch_on.png")?;
+
+    // The cells that place walls, and the kind of wall, texture and health each gets.
+    let wall_config = &config.wall_config;
+    let mut wall_kinds = HashMap::new();
+    wall_kinds.insert('W', (ObjectType::Wall, wall, 1));
+    wall_kinds.insert('B', (ObjectType::BreakableWall, texture_registry.get_handle(&wall_config.breakable_texture)?, wall_config.breakable_health));
+    wall_kinds.insert('G', (ObjectType::GlassWall, texture_registry.get_handle(&wall_config.glass_texture)?, 1));
+    wall_kinds.insert('R', (ObjectType::BumperWall, texture_registry.get_handle(&wall_config.bumper_texture)?, 1));
+
+    if level.gun_concepts.is_empty() {
+        return Err(Error::InvalidLevel {
+            level: level.title.clone(),
+            message: String::from("doesn't give the player any gun concepts"),
+        });
+    }
+    let mut gun_concepts = Vec::new();
+    for level_gun_concept in &level.gun_concepts {
+        let gun_definition = config.gun_definitions.get(&level_gun_concept.kind).ok_or_else(|| Error::InvalidLevel {
+            level: level.title.clone(),
+            message: format!("uses a gun concept, {}, that isn't in the config's gun_definitions", level_gun_concept.kind),
+        })?;
+        if let Some(bullet_kind) = &level_gun_concept.bullet_kind {
+            let problems = bullet_kind.validate();
+            if !problems.is_empty() {
+                return Err(Error::InvalidLevel {
+                    level: level.title.clone(),
+                    message: format!("gives {} bullets that make no sense: {}", level_gun_concept.kind, problems.join("; ")),
+                });
+            }
+        }
+        gun_concepts.push(make_gun_concept(texture_registry, level_gun_concept, gun_definition, &tuning, &config.collider_config)?);
+    }
+
+    // Every enemy starts stationary and facing right unless the level says otherwise.
+    let mut level_enemies = HashMap::new();
+    for level_enemy in &level.enemies {
+        if cell_at(level, level_enemy.row, level_enemy.column).and_then(|cell| config.enemy_config.variant(cell)).is_none() {
+            return Err(Error::InvalidLevel {
+                level: level.title.clone(),
+                message: format!("gives a behavior to row {}, column {}, which isn't an enemy", level_enemy.row, level_enemy.column),
+            });
+        }
+        if let EnemyBehaviorKind::Patrol { path } = &level_enemy.behavior {
+            if path.is_empty() {
+                return Err(Error::InvalidLevel {
+                    level: level.title.clone(),
+                    message: format!("has a patrolling enemy at row {}, column {} with no path", level_enemy.row, level_enemy.column),
+                });
+            }
+        }
+        level_enemies.insert((level_enemy.row - 1, level_enemy.column - 1), level_enemy);
+    }
+
+    // Switches with no doors listed do nothing.
+    let mut level_switches = HashMap::new();
+    for level_switch in &level.switches {
+        if cell_at(level, level_switch.row, level_switch.column) != Some('L') {
+            return Err(Error::InvalidLevel {
+                level: level.title.clone(),
+                message: format!("gives doors to row {}, column {}, which isn't a switch", level_switch.row, level_switch.column),
+            });
+        }
+        for door_cell in &level_switch.doors {
+            let cell = cell_at(level, door_cell.row, door_cell.column);
+            if cell != Some('D') && cell != Some('O') {
+                return Err(Error::InvalidLevel {
+                    level: level.title.clone(),
+                    message: format!("has a switch at row {}, column {} that opens row {}, column {}, which isn't a door", level_switch.row, level_switch.column, door_cell.row, door_cell.column),
+                });
+            }
+        }
+        level_switches.insert((level_switch.row - 1, level_switch.column - 1), level_switch);
+    }
+
+    let player: Player = Player {
+        position: Vector2 {
+            x: 0.0,
+            y: 0.0,
+        },
+        rotation: 0.0,
+        scale: tuning.player_scale,
+        renderable_object: RenderableObject {
+            texture: hand_gun_texture.clone(),
+        },
+        selected_renderable_object: RenderableObject {
+            texture: selected_hand_gun_texture.clone(),
+        },
+        gun_concepts,
+        current_gun_concept_index: 0,
+        collidable_object: CollidableObject {
+            width: f64::from(hand_gun_texture.get_size().0),
+            height: f64::from(hand_gun_texture.get_size().1),
+            shape: config.collider_config.player.clone(),
+        },
+        is_visible: true,
+        was_hit: false,
+    };
+
+    let player = Rc::new(RefCell::new(player));
+
+    let mut simulation = Simulation::new(player.clone(), tuning.clone(), config.collision_config.clone());
+
+    // Switches are hooked up to their doors once every door is made.
+    let mut doors = HashMap::new();
+    let mut switches = Vec::new();
+
+    // Read in a level.
+    for (line_num, line) in level.tiles.iter().enumerate() {
+        for (item_num, item) in line.chars().enumerate() {
+            let position = Vector2 {
+                x: f64::from(item_num as u32 * cell_width + cell_width / 2),
+                y: f64::from(line_num as u32 * cell_height + cell_height / 2),
+            };
+            if let Some((object_type, wall, health)) = wall_kinds.get(&item) {
+                // Walls that can come down or be seen through need ground under them.
+                if *object_type != ObjectType::Wall {
+                    add_ground(&mut simulation, &ground, position, &tuning);
+                }
+
+                let wall = Wall {
+                    position,
+                    rotation: 0.0,
+                    scale: tuning.wall_scale,
+                    renderable_object: RenderableObject {
+                        texture: wall.clone(),
+                    },
+                    collidable_object: CollidableObject {
+                        width: f64::from(wall.get_size().0),
+                        height: f64::from(wall.get_size().1),
+                        shape: config.collider_config.wall.clone(),
+                    },
+                    is_visible: true,
+                    object_type: *object_type,
+                    health: *health,
+                    should_delete: false,
+                };
+                let refcell = Rc::new(RefCell::new(wall));
+                simulation.walls.push(refcell.clone());
+                simulation.add_renderable_at_layer(refcell.clone(), WALL_LAYER);
+                simulation.add_collidable(refcell.clone());
+            } else if item == 'P' {
+                add_ground(&mut simulation, &ground, position, &tuning);
+
+                player.borrow_mut().position = position;
+
+                simulation.add_renderable_at_layer(player.clone(), PLAYER_LAYER);
+                simulation.add_updatable(player.clone());
+                simulation.add_collidable(player.clone());
+            } else if item == 'D' || item == 'O' {
+                add_ground(&mut simulation, &ground, position, &tuning);
+
+                let is_open = item == 'O';
+                let door = Door {
+                    position,
+                    rotation: 0.0,
+                    scale: tuning.wall_scale,
+                    renderable_object: RenderableObject {
+                        texture: door.clone(),
+                    },
+                    collidable_object: CollidableObject {
+                        width: f64::from(door.get_size().0),
+                        height: f64::from(door.get_size().1),
+                        shape: config.collider_config.wall.clone(),
+                    },
+                    is_visible: true,
+                    should_delete: false,
+                    is_open,
+                    is_added: !is_open,
+                };
+                let refcell = Rc::new(RefCell::new(door));
+                if !is_open {
+                    simulation.add_renderable_at_layer(refcell.clone(), WALL_LAYER);
+                    simulation.add_collidable(refcell.clone());
+                }
+                simulation.doors.push(refcell.clone());
+                doors.insert((line_num, item_num), refcell);
+            } else if item == 'L' {
+                add_ground(&mut simulation, &ground, position, &tuning);
+
+                let switch = Switch {
+                    position,
+                    rotation: 0.0,
+                    scale: tuning.wall_scale,
+                    renderable_object: RenderableObject {
+                        texture: switch.clone(),
+                    },
+                    on_renderable_object: RenderableObject {
+                        texture: switch_on.clone(),
+                    },
+                    collidable_object: CollidableObject {
+                        width: f64::from(switch.get_size().0),
+                        height: f64::from(switch.get_size().1),
+                        shape: config.collider_config.wall.clone(),
+                    },
+                    is_visible: true,
+                    is_on: false,
+                    doors: Vec::new(),
+                };
+                let refcell = Rc::new(RefCell::new(switch));
+                simulation.add_renderable_at_layer(refcell.clone(), WALL_LAYER);
+                simulation.add_collidable(refcell.clone());
+                if let Some(level_switch) = level_switches.get(&(line_num, item_num)) {
+                    switches.push((refcell, *level_switch));
+                }
+            } else if item == 'C' {
+                add_ground(&mut simulation, &ground, position, &tuning);
+
+                let hostage = Hostage {
+                    position,
+                    rotation: 0.0,
+                    scale: tuning.enemy_scale,
+                    renderable_object: RenderableObject {
+                        texture: hostage.clone(),
+                    },
+                    collidable_object: CollidableObject {
+                        width: f64::from(hostage.get_size().0),
+                        height: f64::from(hostage.get_size().1),
+                        shape: config.collider_config.hostage.clone(),
+                    },
+                    is_visible: true,
+                    should_delete: false,
+                    was_hit: false,
+                };
+                let refcell = Rc::new(RefCell::new(hostage));
+                simulation.hostages.push(refcell.clone());
+                simulation.add_renderable_at_layer(refcell.clone(), ENEMY_LAYER);
+                simulation.add_collidable(refcell.clone());
+            } else if let Some(variant) = config.enemy_config.variant(item) {
+                add_ground(&mut simulation, &ground, position, &tuning);
+
+                let level_enemy = level_enemies.get(&(line_num, item_num));
+                let enemy_behavior_kind = level_enemy.map_or(EnemyBehaviorKind::Stationary, |level_enemy| level_enemy.behavior.clone());
+                let rotation = level_enemy.map_or(0.0, |level_enemy| level_enemy.facing_degrees.to_radians());
+                let enemy = texture_registry.get_handle(&variant.texture)?;
+                let enemy = Enemy {
+                    position,
+                    rotation,
+                    previous_position: position,
+                    previous_rotation: rotation,
+                    scale: tuning.enemy_scale,
+                    renderable_object: RenderableObject {
+                        texture: enemy.clone(),
+                    },
+                    velocity: Vector2::default(),
+                    should_delete: false,
+                    collidable_object: CollidableObject {
+                        width: f64::from(enemy.get_size().0),
+                        height: f64::from(enemy.get_size().1),
+                        shape: config.collider_config.enemy.clone(),
+                    },
+                    is_visible: true,
+                    enemy_behavior: make_enemy_behavior(texture_registry, &enemy_behavior_kind, &tuning, config)?,
+                    player: player.clone(),
+                    max_health: variant.health,
+                    health: variant.health,
+                    armor_broken_by: variant.armor_broken_by.clone(),
+                    is_armored: !variant.armor_broken_by.is_empty(),
+                    shield_degrees: variant.shield_degrees,
+                };
+                let refcell = Rc::new(RefCell::new(enemy));
+                simulation.enemies.push(refcell.clone());
+                simulation.add_renderable_at_layer(refcell.clone(), ENEMY_LAYER);
+                simulation.add_updatable(refcell.clone());
+                simulation.add_collidable(refcell.clone());
+            } else if item == '_' {
+                add_ground(&mut simulation, &ground, position, &tuning);
+            } else {
+                return Err(Error::InvalidLevel {
+                    level: level.title.clone(),
+                    message: format!("has an unknown cell {:?} at row {}, column {}", item, line_num + 1, item_num + 1),
+                });
+            }
+        }
+    }
+
+    for (switch, level_switch) in switches {
+        switch.borrow_mut().doors = level_switch.doors.iter().filter_map(|door_cell| doors.get(&(door_cell.row - 1, door_cell.column - 1)).cloned()).collect();
+    }
+
+    return Ok(simulation);
+}
+
+// The floor under every cell but a plain wall.
+fn add_ground(simulation: &mut Simulation, texture: &TextureHandle, position: Vector2, tuning: &TuningConfig) {
+    let ground = Ground {
+        position,
+        rotation: 0.0,
+        scale: tuning.ground_scale,
+        renderable_object: RenderableObject {
+            texture: texture.clone(),
+        },
+        is_visible: true,
+    };
+    simulation.add_renderable_at_layer(Rc::new(RefCell::new(ground)), GROUND_LAYER);
+}
+
+// The tile at a row and column counted from 1, if there is one.
+fn cell_at(level: &LevelDocument, row: usize, column: usize) -> Option<char> {
+    level.tiles.get(row.wrapping_sub(1)).and_then(|tiles_row| tiles_row.chars().nth(column.wrapping_sub(1)))
+}
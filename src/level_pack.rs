@@ -0,0 +1,149 @@
+use crate::serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::error::Error;
+use crate::level_document;
+use crate::vfs::Vfs;
+
+// The campaign: chapters of levels, played in order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LevelPack {
+    pub title: String,
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Chapter {
+    pub title: String,
+    // Level names in the mounted `Levels` folder, or paths to level files.
+    pub levels: Vec<String>,
+}
+
+// A level as the menu shows it.
+#[derive(Clone)]
+pub struct LevelListing {
+    pub title: String,
+    pub level: String,
+    // Why the level didn't parse, if it didn't. Picking it shows this instead of playing it.
+    pub error: Option<Rc<Error>>,
+}
+
+pub struct LevelSection {
+    pub title: String,
+    pub levels: Vec<LevelListing>,
+    pub is_custom: bool,
+}
+
+pub fn read_level_pack(vfs: &Vfs, asset_path: &str) -> Result<LevelPack, Error> {
+    let bytes = vfs.read(asset_path).map_err(|err| Error::missing_asset(asset_path, &err))?;
+    serde_json::from_slice(&bytes).map_err(|err| Error::bad_asset(asset_path, &err))
+}
+
+// Every `.json` and `.csv` level in `directory`, sorted by file name. A `.json` wins over a `.csv` with the same name.
+pub fn discover_levels(directory: &Path) -> Vec<String> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).filter(|path| path.is_file()).collect();
+    paths.sort();
+
+    let mut levels: Vec<String> = Vec::new();
+    for path in &paths {
+        let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("");
+        if extension == "csv" && paths.contains(&path.with_extension("json")) {
+            continue;
+        }
+        if extension == "json" || extension == "csv" {
+            levels.push(path.display().to_string());
+        }
+    }
+
+    levels
+}
+
+// Looks up each level's title for the menu. Levels that don't parse are listed by file name, with their error.
+pub fn list_levels(vfs: &Vfs, levels: &[String]) -> Vec<LevelListing> {
+    levels
+        .iter()
+        .map(|level| match level_document::try_read_level_document(vfs, &level_document::level_path(vfs, level)) {
+            Ok(level_document) => LevelListing {
+                title: level_document.title,
+                level: level.clone(),
+                error: None,
+            },
+            Err(err) => LevelListing {
+                title: Path::new(level).file_stem().map_or_else(|| level.clone(), |file_stem| file_stem.to_string_lossy().into_owned()),
+                level: level.clone(),
+                error: Some(Rc::new(err)),
+            },
+        })
+        .collect()
+}
+
+// The campaign chapters followed by a "Custom" section for whatever is in the user levels directory.
+pub fn make_level_sections(vfs: &Vfs, level_pack: &LevelPack, user_levels_directory: &Path) -> Vec<LevelSection> {
+    let mut sections: Vec<LevelSection> = level_pack
+        .chapters
+        .iter()
+        .map(|chapter| LevelSection {
+            title: chapter.title.clone(),
+            levels: list_levels(vfs, &chapter.levels),
+            is_custom: false,
+        })
+        .collect();
+
+    let custom_levels = list_levels(vfs, &discover_levels(user_levels_directory));
+    if !custom_levels.is_empty() {
+        sections.push(LevelSection {
+            title: String::from("Custom"),
+            levels: custom_levels,
+            is_custom: true,
+        });
+    }
+
+    sections
+}
+
+pub fn count_levels(level_sections: &[LevelSection]) -> usize {
+    level_sections.iter().map(|section| section.levels.len()).sum()
+}
+
+// The `level_index`th level in the menu, counting through every section.
+pub fn listing_at(level_sections: &[LevelSection], level_index: usize) -> Option<&LevelListing> {
+    level_sections.iter().flat_map(|section| section.levels.iter()).nth(level_index)
+}
+
+// The levels played in a row after picking the `level_index`th level in the menu, and where in them that level is.
+// The campaign carries on through every chapter; custom levels only lead on to other custom levels.
+pub fn playlist_for(level_sections: &[LevelSection], level_index: usize) -> Option<(Vec<String>, usize)> {
+    let mut levels_before = 0;
+    for (section_index, section) in level_sections.iter().enumerate() {
+        if level_index < levels_before + section.levels.len() {
+            let same_kind_levels_before: usize = level_sections[..section_index].iter().filter(|other_section| other_section.is_custom == section.is_custom).map(|other_section| other_section.levels.len()).sum();
+            return Some((playlist_of(level_sections, section.is_custom), same_kind_levels_before + level_index - levels_before));
+        }
+        levels_before += section.levels.len();
+    }
+
+    None
+}
+
+fn playlist_of(level_sections: &[LevelSection], is_custom: bool) -> Vec<String> {
+    level_sections.iter().filter(|section| section.is_custom == is_custom).flat_map(|section| section.levels.iter().map(|listing| listing.level.clone())).collect()
+}
+
+// Finds the playlist a level belongs to, for jumping straight into it (say, to watch a replay).
+pub fn playlist_containing(level_sections: &[LevelSection], level: &str) -> (Vec<String>, usize) {
+    for &is_custom in &[false, true] {
+        let playlist = playlist_of(level_sections, is_custom);
+        if let Some(playlist_index) = playlist.iter().position(|playlist_level| playlist_level == level) {
+            return (playlist, playlist_index);
+        }
+    }
+
+    (vec![String::from(level)], 0)
+}
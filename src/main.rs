@@ -11,6 +11,7 @@ extern crate find_folder;
 extern crate gfx_device_gl;
 extern crate glutin_window;
 extern crate graphics;
+extern crate image;
 extern crate nalgebra;
 extern crate ncollide2d;
 extern crate piston;
@@ -21,9 +22,9 @@ extern crate time;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
+use std::ops::Deref;
 use std::path::Path;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::mpsc::channel;
 use std::thread;
@@ -32,117 +33,113 @@ use std::time::Duration;
 use piston_window::*;
 
 use crate::asset_loader::AssetLoader;
-use crate::collidable_object::CollidableObject;
 use crate::config::Config;
-use crate::enemy::Enemy;
+use crate::error::Error;
+use crate::error_screen::ErrorScreen;
+use crate::file_watcher::FileWatcher;
+use crate::fixed_timestep::FixedTimestep;
 use crate::fps_counter::FpsCounter;
 use crate::game_state::GameState;
 use crate::game_state::GameStateType;
 use crate::game_state::UpdateResult;
 use crate::game_state::UpdateResultType;
-use crate::ground::Ground;
-use crate::gun_axe_behavior::GunAxeBehavior;
-use crate::gun_axe_concept::GunAxeConcept;
-use crate::gun_concept::GunConcept;
-use crate::hand_gun_behavior::HandGunBehavior;
-use crate::hand_gun_concept::HandGunConcept;
+use crate::level_document::LevelDocument;
+use crate::level_pack::LevelPack;
+use crate::level_pack::LevelSection;
 use crate::menu_screen::MenuScreen;
-use crate::player::Player;
-use crate::renderable_object::RenderableObject;
-use crate::shot_gun_behavior::ShotGunBehavior;
-use crate::shot_gun_concept::ShotGunConcept;
-use crate::tommy_gun_concept::TommyGunConcept;
-use crate::tommy_gun_behavior::TommyGunBehavior;
+use crate::replay::Replay;
+use crate::replay::ReplayPlayback;
+use crate::replay::REPLAY_VERSION;
 use crate::sound_manager::SoundManager;
 use crate::texture_manager::TextureManager;
 use crate::ui_bundle::UiBundle;
 use crate::ui_widget_ids::Ids;
 use crate::vector2::*;
+use crate::vfs::Vfs;
 use crate::victory_screen::VictoryScreen;
-use crate::wall::Wall;
-use crate::world::GameEndedState;
 use crate::world::World;
 
 mod asset_loader;
 mod bullet;
+mod bullet_behavior;
+mod chase_enemy_behavior;
 mod collidable;
 mod collidable_object;
+mod collision_grid;
+mod door;
 mod enemy;
+mod enemy_behavior;
+mod error;
+mod error_screen;
+mod explosive_bullet_behavior;
+mod file_watcher;
+mod fixed_timestep;
 mod fps_counter;
 mod game_object;
 mod game_state;
 mod game_state_utils;
+mod generic_gun_behavior;
+mod generic_gun_concept;
 mod ground;
+mod headless;
+mod heavy_bullet_behavior;
+mod hostage;
 mod gun;
-mod gun_axe_behavior;
 mod gun_behavior;
-mod hand_gun_behavior;
+mod headless_texture_registry;
 mod input;
+mod level_document;
+mod level_lint;
+mod level_loader;
+mod level_pack;
 mod menu_screen;
+mod normal_bullet_behavior;
 mod gun_concept_utils;
 mod object_type;
+mod patrol_enemy_behavior;
+mod piercing_bullet_behavior;
 mod player;
 mod render_utils;
 mod renderable;
 mod renderable_object;
+mod replay;
+mod ricochet_bullet_behavior;
+mod simulation;
+mod solver;
 mod sound_manager;
+mod split_bullet_behavior;
+mod stationary_enemy_behavior;
+mod switch;
+mod texture_handle;
 mod texture_manager;
+mod texture_registry;
+mod turret_enemy_behavior;
 mod ui_bundle;
 mod ui_widget_ids;
 mod updatable;
 mod vector2;
+mod vfs;
 mod victory_screen;
 mod wall;
 mod world;
-mod shot_gun_behavior;
 mod gun_concept;
-mod shot_gun_concept;
-mod hand_gun_concept;
-mod gun_axe_concept;
-mod tommy_gun_concept;
-mod tommy_gun_behavior;
 mod config;
 
-const WIDTH: u32 = 1280;
-const HEIGHT: u32 = 720;
-
 const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
-const GRID_WIDTH: u32 = 32;
-const GRID_HEIGHT: u32 = 18;
-const CELL_WIDTH: u32 = WIDTH / GRID_WIDTH;
-const CELL_HEIGHT: u32 = HEIGHT / GRID_HEIGHT;
-const PLAYER_SCALE: f64 = 0.5;
-const WALL_SCALE: f64 = 1.0;
-const ENEMY_SCALE: f64 = 1.0;
-const GROUND_SCALE: f64 = 1.0;
-
-const GROUND_LAYER: usize = 0;
-const WALL_LAYER: usize = 0;
-const ENEMY_LAYER: usize = 1;
-const PLAYER_LAYER: usize = 1;
 
 pub struct App<'a> {
     window: piston_window::PistonWindow,
     game_state: Box<GameState>,
-    texture_manager: TextureManager,
-    sound_manager: SoundManager,
+    texture_manager: Rc<RefCell<TextureManager>>,
+    sound_manager: Rc<RefCell<SoundManager>>,
+    vfs: Rc<Vfs>,
+    level_sections: Rc<Vec<LevelSection>>,
     level_index: usize,
-    world_list: Rc<Vec<&'a str>>,
+    // The levels being played through, in order: the campaign, or the custom levels.
+    world_list: Vec<String>,
+    // Watches the config and the level being played, while there is one, to restart it when either is saved.
+    level_watcher: Option<FileWatcher>,
     ui_bundle: UiBundle<'a>,
-    asset_loader: Rc<AssetLoader>,
-}
-
-fn read_config_from_file<P: AsRef<Path>>(path: P) -> Config {
-    let path_ref = path.as_ref();
-    let path_str = path_ref.as_os_str().to_os_string().into_string().unwrap();
-
-    let file = File::open(path).unwrap_or_else(|_| {
-        panic!(format!("could not find config file at path {}", path_str))
-    });
-    let reader = BufReader::new(file);
-    serde_json::from_reader(reader).unwrap_or_else(|_| {
-        panic!(format!("failed to parse config file at path {}", path_str))
-    })
 }
 
 impl<'a> App<'a> {
@@ -158,6 +155,14 @@ impl<'a> App<'a> {
     }
 
     fn update(&mut self, key_states: &HashMap<Key, input::ButtonState>, mouse_states: &HashMap<MouseButton, input::ButtonState>, mouse_pos: &Vector2, args: UpdateArgs) {
+        let files_changed = match &mut self.level_watcher {
+            Some(level_watcher) => level_watcher.poll(),
+            None => false,
+        };
+        if files_changed {
+            self.start_level();
+        }
+
         let update_result = self.game_state.update(&key_states, &mouse_states, &mouse_pos, &mut self.ui_bundle, args);
         self.advance_game_state(update_result);
     }
@@ -168,9 +173,19 @@ impl<'a> App<'a> {
                 // do nothing
             }
             UpdateResultType::Success => {
-                self.level_index = update_result.result_code as usize;
-                let world = load_level(&mut self.texture_manager, &mut self.sound_manager, self.world_list[self.level_index], self.asset_loader.clone());
-                self.game_state = Box::new(world);
+                let level_index = update_result.result_code as usize;
+                if let Some(err) = level_pack::listing_at(&self.level_sections, level_index).and_then(|listing| listing.error.clone()) {
+                    self.level_watcher = None;
+                    self.game_state = Box::new(make_error_screen(&err));
+                    return;
+                }
+
+                // The menu only offers levels it listed, but stay on it rather than start nothing.
+                if let Some((world_list, level_index)) = level_pack::playlist_for(&self.level_sections, level_index) {
+                    self.world_list = world_list;
+                    self.level_index = level_index;
+                    self.start_level();
+                }
             }
             UpdateResultType::Fail => {
                 // do nothing
@@ -198,18 +213,50 @@ impl<'a> App<'a> {
             self.advance_game_state_from_world_select(update_result);
         } else if GameStateType::World == self.game_state.get_type() || GameStateType::Victory == self.game_state.get_type() {
             self.advance_game_state_from_world(update_result);
+        } else if GameStateType::Error == self.game_state.get_type() {
+            self.advance_game_state_from_error(update_result);
+        }
+    }
+
+    fn advance_game_state_from_error(&mut self, update_result: UpdateResult) {
+        if let UpdateResultType::Success = update_result.result_type {
+            self.level_watcher = None;
+            self.game_state = match make_menu_screen(self.level_sections.clone(), &mut self.texture_manager.borrow_mut()) {
+                Ok(menu_screen) => Box::new(menu_screen),
+                Err(err) => Box::new(make_error_screen(&err)),
+            };
         }
     }
 
+    // A level or config that won't load shows the error screen rather than ending the session.
+    // Either way the files stay watched, so fixing the mistake brings the level straight back.
+    fn start_level(&mut self) {
+        let level_name = &self.world_list[self.level_index];
+        let config = config::read_default_config();
+        if config.as_ref().map_or(true, |config| config.level_config.hot_reload) {
+            let level_path = level_document::level_path(&self.vfs, level_name);
+            let level_source_path = if Path::new(&level_path).is_file() { Some(PathBuf::from(level_path)) } else { self.vfs.source_path(&level_path) };
+            self.level_watcher = Some(FileWatcher::new(config::default_config_path().into_iter().chain(level_source_path).collect()));
+        } else {
+            self.level_watcher = None;
+        }
+
+        let world = config.and_then(|config| make_world(&self.vfs, self.texture_manager.clone(), self.sound_manager.clone(), level_name, config, None));
+        self.game_state = match world {
+            Ok(world) => Box::new(world),
+            Err(err) => Box::new(make_error_screen(&err)),
+        };
+    }
+
     fn advance_level(&mut self) {
         if self.level_index > self.world_list.len() {
             self.level_index = 0;
         }
 
         if self.level_index < self.world_list.len() {
-            let world = load_level(&mut self.texture_manager, &mut self.sound_manager, self.world_list[self.level_index], self.asset_loader.clone());
-            self.game_state = Box::new(world);
+            self.start_level();
         } else if self.level_index == self.world_list.len() {
+            self.level_watcher = None;
             self.game_state = Box::new(VictoryScreen {
                 image_map: conrod_core::image::Map::new(),
             });
@@ -217,297 +264,36 @@ impl<'a> App<'a> {
     }
 }
 
-fn load_level(texture_manager: &mut TextureManager, sound_manager: &mut SoundManager, level_name: &str, asset_loader: Rc<AssetLoader>) -> World {
-    let hand_gun_texture = texture_manager.get("textures\\hand-gun_square.png");
-    let selected_hand_gun_texture = texture_manager.get("textures\\hand-gun_square_selected.png");
-    let gun_gun = texture_manager.get("textures\\GunGunV1.png");
-    let gun_gun_selected = texture_manager.get("textures\\GunGunV1_selected.png");
-    let gun_axe_texture = texture_manager.get("textures\\GunaxeV1.png");
-    let gun_axe_texture_selected = texture_manager.get("textures\\GunaxeV1_selected.png");
-    let shot_gun_texture = texture_manager.get("textures\\shotgun.png");
-    let shot_gun_texture_selected = texture_manager.get("textures\\shotgun_selected.png");
-    let tommy_gun_texture = texture_manager.get("textures\\shotgun.png");
-    let tommy_gun_texture_selected = texture_manager.get("textures\\shotgun_selected.png");
-    let bullet = texture_manager.get("textures\\bullet.png");
-    let wall = texture_manager.get("textures\\brick_square.png");
-    let enemy = texture_manager.get("textures\\enemy.png");
-    let ground = texture_manager.get("textures\\ground.png");
-    let mut image_map = conrod_core::image::Map::new();
-
-    let gun_sound = sound_manager.get("sounds\\boom.ogg");
-
-    let hand_gun_image: G2dTexture = asset_loader.load_texture("textures/GunGunV1.png");
-    let hand_gun_image_id = image_map.insert(hand_gun_image);
-    let selected_hand_gun_image: G2dTexture = asset_loader.load_texture("textures/GunGunV1_selected.png");
-    let selected_hand_gun_image_id = image_map.insert(selected_hand_gun_image);
-    let bullet_image: G2dTexture = asset_loader.load_texture("textures/bullet.png");
-    let bullet_image_id = image_map.insert(bullet_image);
-    let hand_gun: Rc<RefCell<GunConcept>> = Rc::new(RefCell::new(HandGunConcept {
-        gun_sound: gun_sound.clone(),
-        gun_texture: gun_gun.clone(),
-        gun_image_id: hand_gun_image_id,
-        selected_gun_texture: gun_gun_selected.clone(),
-        selected_gun_image_id: selected_hand_gun_image_id,
-        bullet_texture: bullet.clone(),
-        bullet_image_id,
-        bullet_sound: sound_manager.get("sounds\\boop.ogg"),
-        gun_behavior: Box::new(HandGunBehavior {
-            should_delete: false,
-        }),
-        shots_taken: 0,
-        guns: Vec::new(),
-        has_shot_bullet: false,
-        is_selected: false,
-    }));
-
-    let config_path: std::path::PathBuf = find_folder::Search::ParentsThenKids(3, 3).for_folder("config").unwrap();
-    let config = read_config_from_file(config_path.join("default.json"));
-    println!("{:#?}", config);
-
-    let gun_axe_image: G2dTexture = asset_loader.load_texture("textures/GunaxeV1.png");
-    let gun_axe_image_id = image_map.insert(gun_axe_image);
-    let selected_gun_axe_image: G2dTexture = asset_loader.load_texture("textures/GunaxeV1_selected.png");
-    let selected_gun_axe_image_id = image_map.insert(selected_gun_axe_image);
-    let gun_axe: Rc<RefCell<GunConcept>> = Rc::new(RefCell::new(GunAxeConcept {
-        gun_sound: gun_sound.clone(),
-        gun_texture: gun_axe_texture.clone(),
-        gun_image_id: gun_axe_image_id,
-        selected_gun_texture: gun_axe_texture_selected.clone(),
-        selected_gun_image_id: selected_gun_axe_image_id,
-        bullet_texture: bullet.clone(),
-        bullet_image_id,
-        bullet_sound: sound_manager.get("sounds\\boop.ogg"),
-        gun_behavior: Box::new(GunAxeBehavior {
-            should_delete: false,
-            has_gun_depth: true,
-            gun_depth: config.gunaxe_gun_config.gun_depth,
-        }),
-        shots_taken: 0,
-        guns: Vec::new(),
-        has_shot_bullet: false,
-        is_selected: false,
-    }));
-
-    let tommy_gun_image: G2dTexture = asset_loader.load_texture("textures/shotgun.png");
-    let tommy_gun_image_id = image_map.insert(tommy_gun_image);
-    let selected_tommy_gun_image: G2dTexture = asset_loader.load_texture("textures/shotgun_selected.png");
-    let selected_tommy_gun_image_id = image_map.insert(selected_tommy_gun_image);
-    let tommy_gun_concept: Rc<RefCell<GunConcept>> = Rc::new(RefCell::new(TommyGunConcept {
-        gun_sound: gun_sound.clone(),
-        gun_texture: tommy_gun_texture.clone(),
-        gun_image_id: tommy_gun_image_id,
-        selected_gun_texture: tommy_gun_texture_selected.clone(),
-        selected_gun_image_id: selected_tommy_gun_image_id,
-        bullet_texture: bullet.clone(),
-        bullet_image_id,
-        bullet_sound: sound_manager.get("sounds\\boop.ogg"),
-        gun_behavior: Box::new(TommyGunBehavior {
-            should_delete: false,
-        }),
-        shots_taken: 0,
-        guns: Vec::new(),
-        has_shot_bullet: false,
-        is_selected: false,
-    }));
-
-    let shot_gun_image: G2dTexture = asset_loader.load_texture("textures/shotgun.png");
-    let shot_gun_image_id = image_map.insert(shot_gun_image);
-    let selected_shot_gun_image: G2dTexture = asset_loader.load_texture("textures/shotgun_selected.png");
-    let selected_shot_gun_image_id = image_map.insert(selected_shot_gun_image);
-    let shot_gun_concept: Rc<RefCell<GunConcept>> = Rc::new(RefCell::new(ShotGunConcept {
-        gun_sound: gun_sound.clone(),
-        gun_texture: shot_gun_texture.clone(),
-        gun_image_id: shot_gun_image_id,
-        selected_gun_texture: shot_gun_texture_selected.clone(),
-        selected_gun_image_id: selected_shot_gun_image_id,
-        bullet_texture: bullet.clone(),
-        bullet_image_id,
-        bullet_sound: sound_manager.get("sounds\\boop.ogg"),
-        gun_behavior: Box::new(ShotGunBehavior {
-            should_delete: false,
-        }),
-        shots_taken: 0,
-        guns: Vec::new(),
-        has_shot_bullet: false,
-        is_selected: false,
-    }));
-
-    let gun_concepts: Vec<Rc<RefCell<GunConcept>>> = vec![hand_gun, gun_axe, tommy_gun_concept, shot_gun_concept];
-
-    let player: Player = Player {
-        position: Vector2 {
-            x: 0.0,
-            y: 0.0,
-        },
-        rotation: 0.0,
-        scale: PLAYER_SCALE,
-        renderable_object: RenderableObject {
-            texture: hand_gun_texture.clone(),
-        },
-        selected_renderable_object: RenderableObject {
-            texture: selected_hand_gun_texture.clone(),
-        },
-        gun_concepts,
-        current_gun_concept_index: 0,
-        is_visible: true,
-    };
-
-    let player = Rc::new(RefCell::new(player));
-
-    let (sender, receiver) = channel();
-
-    let mut world: World = World {
-        renderables: Vec::new(),
-        collidables: Vec::new(),
-        updatables: Vec::new(),
-        game_ended_state: GameEndedState {
-            game_ended: false,
-            won: false,
-        },
-        player: player.clone(),
-        receiver,
-        should_display_level_name: true,
-        name: String::from(level_name),
-        fps_counter: FpsCounter::default(),
-        image_map,
-    };
+fn level_banner_text(level: &LevelDocument) -> String {
+    let mut text = level.title.clone();
+    if !level.author.is_empty() {
+        text += &format!("\nby {}", level.author);
+    }
+    if !level.description.is_empty() {
+        text += &format!("\n{}", level.description);
+    }
+    text
+}
 
-    let file_name = format!("assets\\Levels\\{}.csv", level_name);
-    let file_result = File::open(file_name.clone());
+fn make_world(vfs: &Vfs, texture_manager: Rc<RefCell<TextureManager>>, sound_manager: Rc<RefCell<SoundManager>>, level_name: &str, config: Config, replay_playback: Option<ReplayPlayback>) -> Result<World, Error> {
+    let level = level_document::read_level_document(vfs, level_name)?;
+    let simulation = level_loader::build_level(&mut *texture_manager.borrow_mut(), &level, &config)?;
 
-    let file = match file_result {
-        Ok(f) => f,
-        Err(err) => {
-            panic!("Couldn't read file from {}, err: {}", file_name, err);
-        }
-    };
-    //    let mut csv_rdr = csv::Reader::from_reader(file);
-    let mut csv_rdr = csv::ReaderBuilder::new().has_headers(false).from_reader(file);
-
-    // Make sure it's the right size.
-    //    let mut i = 0;
-    //    for record_result in csv_rdr.records() {
-    //        let mut record = match record_result {
-    //            Ok(r) => r,
-    //            Err(err) => { panic!("Couldn't read line {} from {}", i, file_name); }
-    //        };
-    //
-    //        assert!(record.len() as u32 == GRID_WIDTH);
-    //        i += 1;
-    //    }
-    //    assert!(i == GRID_HEIGHT);
-
-    //    let new_csv_rdr = || csv::Reader::from_file(format!("assets\\Levels\\{}.csv", level_name)).unwrap().has_headers(false);
-    //    let mut index_data = io::Cursor::new(Vec::new());
-    //    create_index(new_csv_rdr(), index_data.by_ref()).unwrap();
-    //    let mut index = Indexed::open(new_csv_rdr(), index_data).unwrap();
-
-    // Read in a level.
-    for (line_num, record_result) in csv_rdr.records().enumerate() {
-        let line = match record_result {
-            Ok(r) => r,
-            Err(err) => {
-                panic!("Couldn't read line {} from {}, err: {}", line_num, file_name, err);
-            }
-        };
-        for (item_num, item) in line.iter().enumerate() {
-            if item == "W" {
-                let wall = Wall {
-                    position: Vector2 {
-                        x: f64::from(item_num as u32 * CELL_WIDTH + CELL_WIDTH / 2),
-                        y: f64::from(line_num as u32 * CELL_HEIGHT + CELL_HEIGHT / 2),
-                    },
-                    rotation: 0.0,
-                    scale: WALL_SCALE,
-                    renderable_object: RenderableObject {
-                        texture: wall.clone(),
-                    },
-                    collidable_object: CollidableObject {
-                        width: f64::from(wall.get_size().0),
-                        height: f64::from(wall.get_size().1),
-                    },
-                    is_visible: true,
-                };
-                let refcell = Rc::new(RefCell::new(wall));
-                world.add_renderable_at_layer(refcell.clone(), WALL_LAYER);
-                world.add_collidable(refcell.clone());
-            } else if item == "P" {
-                let ground = Ground {
-                    position: Vector2 {
-                        x: f64::from(item_num as u32 * CELL_WIDTH + CELL_WIDTH / 2),
-                        y: f64::from(line_num as u32 * CELL_HEIGHT + CELL_HEIGHT / 2),
-                    },
-                    rotation: 0.0,
-                    scale: GROUND_SCALE,
-                    renderable_object: RenderableObject {
-                        texture: ground.clone(),
-                    },
-                    is_visible: true,
-                };
-                let refcell = Rc::new(RefCell::new(ground));
-                world.add_renderable_at_layer(refcell.clone(), GROUND_LAYER);
-
-                player.borrow_mut().position.x = f64::from(item_num as u32 * CELL_WIDTH + CELL_WIDTH / 2);
-                player.borrow_mut().position.y = f64::from(line_num as u32 * CELL_HEIGHT + CELL_HEIGHT / 2);
-
-                world.add_renderable_at_layer(player.clone(), PLAYER_LAYER);
-                world.add_updatable(player.clone());
-            } else if item == "E" {
-                let ground = Ground {
-                    position: Vector2 {
-                        x: f64::from(item_num as u32 * CELL_WIDTH + CELL_WIDTH / 2),
-                        y: f64::from(line_num as u32 * CELL_HEIGHT + CELL_HEIGHT / 2),
-                    },
-                    rotation: 0.0,
-                    scale: GROUND_SCALE,
-                    renderable_object: RenderableObject {
-                        texture: ground.clone(),
-                    },
-                    is_visible: true,
-                };
-                let refcell = Rc::new(RefCell::new(ground));
-                world.add_renderable_at_layer(refcell.clone(), GROUND_LAYER);
-
-                let enemy = Enemy {
-                    position: Vector2 {
-                        x: f64::from(item_num as u32 * CELL_WIDTH + CELL_WIDTH / 2),
-                        y: f64::from(line_num as u32 * CELL_HEIGHT + CELL_HEIGHT / 2),
-                    },
-                    rotation: 0.0,
-                    scale: ENEMY_SCALE,
-                    renderable_object: RenderableObject {
-                        texture: enemy.clone(),
-                    },
-                    should_delete: false,
-                    collidable_object: CollidableObject {
-                        width: f64::from(enemy.get_size().0),
-                        height: f64::from(enemy.get_size().1),
-                    },
-                    is_visible: true,
-                };
-                let refcell = Rc::new(RefCell::new(enemy));
-                world.add_renderable_at_layer(refcell.clone(), ENEMY_LAYER);
-                world.add_collidable(refcell.clone());
-            } else if item == "_" {
-                // todo: make this a func and factor out from 3 ifs above
-                let ground = Ground {
-                    position: Vector2 {
-                        x: f64::from(item_num as u32 * CELL_WIDTH + CELL_WIDTH / 2),
-                        y: f64::from(line_num as u32 * CELL_HEIGHT + CELL_HEIGHT / 2),
-                    },
-                    rotation: 0.0,
-                    scale: GROUND_SCALE,
-                    renderable_object: RenderableObject {
-                        texture: ground.clone(),
-                    },
-                    is_visible: true,
-                };
-                let refcell = Rc::new(RefCell::new(ground));
-                world.add_renderable_at_layer(refcell.clone(), GROUND_LAYER);
+    // The HUD draws the gun concepts through conrod, which keeps its own map of images.
+    let mut image_map = conrod_core::image::Map::new();
+    let mut image_ids_by_texture_name = HashMap::new();
+    for gun_concept in &simulation.player.borrow().gun_concepts {
+        let gun_concept = gun_concept.borrow();
+        for texture in &[gun_concept.gun_texture(), gun_concept.selected_gun_texture(), gun_concept.bullet_texture()] {
+            if !image_ids_by_texture_name.contains_key(&texture.name) {
+                let image: G2dTexture = texture_manager.borrow_mut().get(&texture.name)?.deref().clone();
+                image_ids_by_texture_name.insert(texture.name.clone(), image_map.insert(image));
             }
         }
     }
 
+    let (sender, receiver) = channel();
+
     // Spawn one second timer.
     thread::spawn(move || {
         thread::sleep(Duration::from_secs(1));
@@ -517,74 +303,192 @@ fn load_level(texture_manager: &mut TextureManager, sound_manager: &mut SoundMan
         let _ = sender.send(0);
     });
 
-    return world;
+    let replay_recording = if replay_playback.is_none() && config.replay_config.record_replays {
+        Some(Replay {
+            version: REPLAY_VERSION,
+            level_name: String::from(level_name),
+            config: config.clone(),
+            ticks: Vec::new(),
+        })
+    } else {
+        None
+    };
+
+    Ok(World {
+        simulation,
+        fixed_timestep: FixedTimestep::new(config.game_loop_config.ticks_per_second, config.game_loop_config.max_ticks_per_update),
+        tick_key_states: HashMap::new(),
+        tick_mouse_states: HashMap::new(),
+        tick_mouse_pos: Vector2::default(),
+        replay_recording,
+        replay_playback,
+        receiver,
+        should_display_level_name: true,
+        name: level_banner_text(&level),
+        par: level.par,
+        fps_counter: FpsCounter::default(),
+        image_map,
+        image_ids_by_texture_name,
+        texture_manager,
+        sound_manager,
+    })
 }
 
-fn make_menu_screen<'a>(world_list: Rc<Vec<&'a str>>, asset_loader: &AssetLoader) -> MenuScreen<'a> {
+fn make_menu_screen(level_sections: Rc<Vec<LevelSection>>, texture_manager: &mut TextureManager) -> Result<MenuScreen, Error> {
     let mut image_map = conrod_core::image::Map::new();
 
-    let logo_texture: G2dTexture = asset_loader.load_texture("textures/GunGunV1.png");
+    let logo_texture: G2dTexture = texture_manager.get("textures/GunGunV1.png")?.deref().clone();
     let logo_image_id = image_map.insert(logo_texture);
 
-    MenuScreen {
-        world_list,
+    Ok(MenuScreen {
+        level_sections,
         selected_world_index: 0,
         fps_counter: FpsCounter::default(),
         image_map,
         logo_image_id,
+    })
+}
+
+fn make_error_screen(err: &Error) -> ErrorScreen {
+    println!("{}", err);
+    ErrorScreen {
+        message: err.to_string(),
+        image_map: conrod_core::image::Map::new(),
     }
 }
 
+// The command line tools and startup have nowhere to show an error screen, so they print the error and quit.
+fn exit_on_error<T>(result: Result<T, Error>) -> T {
+    result.unwrap_or_else(|err| {
+        println!("{}", err);
+        std::process::exit(1);
+    })
+}
+
+fn parse_argument<T: std::str::FromStr>(argument: &str, name: &str) -> Result<T, Error>
+where
+    T::Err: std::fmt::Display,
+{
+    argument.parse().map_err(|err| Error::BadArgument {
+        argument: String::from(argument),
+        message: format!("{} must be a number: {}", name, err),
+    })
+}
+
 fn main() {
-    let window_settings = WindowSettings::new("piston_shooty", [WIDTH, HEIGHT]);
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() >= 3 && args[1] == "simulate" {
+        let seconds = exit_on_error(args.get(3).map_or(Ok(10.0), |seconds| parse_argument(seconds, "seconds")));
+        exit_on_error(headless::run_simulation(&args[2], seconds));
+        return;
+    }
 
-    let assets_path: std::path::PathBuf = find_folder::Search::ParentsThenKids(3, 3).for_folder("assets").unwrap();
+    if args.len() >= 3 && args[1] == "lint" {
+        let config = exit_on_error(config::read_default_config());
+        let vfs = exit_on_error(Vfs::from_config(&config));
+        let mut all_ok = true;
+        for level in &args[2..] {
+            all_ok &= level_lint::run_lint(&vfs, &config, level);
+        }
+        if !all_ok {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.len() >= 4 && args[1] == "pack" {
+        let num_assets = exit_on_error(vfs::write_packed_archive(Path::new(&args[2]), Path::new(&args[3])).map_err(|err| Error::Pack {
+            path: args[3].clone(),
+            message: err.to_string(),
+        }));
+        println!("Packed {} assets into {}", num_assets, args[3]);
+        return;
+    }
+
+    if args.len() >= 3 && args[1] == "solve" {
+        let mut config = exit_on_error(config::read_default_config());
+        if let Some(max_gun_shots) = args.get(3) {
+            config.solver_config.max_gun_shots = exit_on_error(parse_argument(max_gun_shots, "max gun shots"));
+        }
+        if !exit_on_error(solver::run_solver(&args[2], config)) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let replay = if args.len() >= 3 && args[1] == "replay" {
+        let replay = exit_on_error(replay::read_replay_from_file(&args[2]));
+        if args.iter().any(|arg| arg == "--headless") {
+            exit_on_error(headless::run_replay(&replay));
+            return;
+        }
+        Some(replay)
+    } else {
+        None
+    };
+
+    let config = exit_on_error(config::read_default_config());
+    let window_width = config.tuning_config.window_width;
+    let window_height = config.tuning_config.window_height;
+
+    let window_settings = WindowSettings::new("piston_shooty", [window_width, window_height]);
+    let vfs = Rc::new(exit_on_error(Vfs::from_config(&config)));
 
     let mut window: piston_window::PistonWindow = window_settings.exit_on_esc(true).build().unwrap();
 
     let asset_loader = AssetLoader {
-        assets_path,
+        vfs: vfs.clone(),
         factory: window.factory.clone(),
     };
     let asset_loader = Rc::new(asset_loader);
 
-    let texture_manager = TextureManager {
+    let texture_manager = Rc::new(RefCell::new(TextureManager {
         asset_loader: asset_loader.clone(),
         textures_by_filename: HashMap::new(),
-    };
+    }));
 
-    let sound_manager = SoundManager {
+    let sound_manager = Rc::new(RefCell::new(SoundManager {
         asset_loader: asset_loader.clone(),
         sounds_by_filename: HashMap::new(),
-    };
+    }));
 
-    let world_list = Rc::new(vec!["ShotGunGun", "Sunday-Gunday", "Multi-Level Mark-hitting"]);
+    // Without the campaign, custom levels can still be played once the error's been read.
+    let (level_pack, level_pack_error) = match level_pack::read_level_pack(&vfs, &config.level_config.level_pack) {
+        Ok(level_pack) => (level_pack, None),
+        Err(err) => (
+            LevelPack {
+                title: String::new(),
+                chapters: Vec::new(),
+            },
+            Some(err),
+        ),
+    };
+    let level_sections = Rc::new(level_pack::make_level_sections(&vfs, &level_pack, Path::new(&config.level_config.user_levels_directory)));
 
-    let menu_screen = make_menu_screen(world_list.clone(), &asset_loader);
+    let first_game_state: Box<GameState> = match level_pack_error.map_or_else(|| make_menu_screen(level_sections.clone(), &mut texture_manager.borrow_mut()), Err) {
+        Ok(menu_screen) => Box::new(menu_screen),
+        Err(err) => Box::new(make_error_screen(&err)),
+    };
 
     let mut key_states: HashMap<Key, input::ButtonState> = HashMap::new();
     let mut mouse_states: HashMap<MouseButton, input::ButtonState> = HashMap::new();
     let mut mouse_pos = Vector2::default();
 
-    // todo: dupes
-    let assets_path: std::path::PathBuf = find_folder::Search::ParentsThenKids(3, 3).for_folder("assets").unwrap();
-
-    let mut ui = conrod_core::UiBuilder::new([f64::from(WIDTH), f64::from(HEIGHT)]).build();
+    let mut ui = conrod_core::UiBuilder::new([f64::from(window_width), f64::from(window_height)]).build();
 
-    let font_path = assets_path.join("Roboto-Regular.ttf");
-    ui.fonts.insert_from_file(font_path).unwrap();
+    ui.fonts.insert(exit_on_error(asset_loader.load_font("Roboto-Regular.ttf")));
 
     let (glyph_cache, text_texture_cache) = {
         const SCALE_TOLERANCE: f32 = 0.1;
         const POSITION_TOLERANCE: f32 = 0.1;
 
-        let cache = conrod_core::text::GlyphCache::builder().dimensions(WIDTH, HEIGHT).scale_tolerance(SCALE_TOLERANCE).position_tolerance(POSITION_TOLERANCE).build();
+        let cache = conrod_core::text::GlyphCache::builder().dimensions(window_width, window_height).scale_tolerance(SCALE_TOLERANCE).position_tolerance(POSITION_TOLERANCE).build();
 
-        let buffer_len = WIDTH as usize * HEIGHT as usize;
+        let buffer_len = window_width as usize * window_height as usize;
         let init = vec![128; buffer_len];
         let settings = TextureSettings::new();
         let factory = &mut window.factory;
-        let texture = G2dTexture::from_memory_alpha(factory, &init, WIDTH, HEIGHT, &settings).unwrap();
+        let texture = G2dTexture::from_memory_alpha(factory, &init, window_width, window_height, &settings).unwrap();
 
         (cache, texture)
     };
@@ -600,17 +504,35 @@ fn main() {
 
     let mut app = App {
         window,
-        game_state: Box::new(menu_screen),
+        game_state: first_game_state,
         texture_manager,
         sound_manager,
+        vfs,
+        level_sections,
         level_index: 0,
-        world_list,
+        world_list: Vec::new(),
+        level_watcher: None,
         ui_bundle,
-        asset_loader,
     };
     app.window.set_max_fps(u64::max_value());
 
-    // TODO: Why is args.dt locked to 120fps for UpdateArgs?
+    if let Some(replay) = replay {
+        let (world_list, level_index) = level_pack::playlist_containing(&app.level_sections, &replay.level_name);
+        app.world_list = world_list;
+        app.level_index = level_index;
+        let level_name = replay.level_name.clone();
+        let config = replay.config.clone();
+        let world = make_world(&app.vfs, app.texture_manager.clone(), app.sound_manager.clone(), &level_name, config, Some(ReplayPlayback {
+            replay,
+            next_tick_index: 0,
+        }));
+        app.game_state = match world {
+            Ok(world) => Box::new(world),
+            Err(err) => Box::new(make_error_screen(&err)),
+        };
+    }
+
+    // Piston's update events only feed the World's fixed timestep; how often they arrive doesn't change the simulation.
     while let Some(event) = app.window.next() {
         // Convert the piston event to a conrod event.
         let size = app.window.size();
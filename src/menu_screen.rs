@@ -6,6 +6,8 @@ use crate::game_state::UpdateResultType;
 use crate::game_state::UPDATE_RESULT_RUNNING;
 use crate::game_state_utils;
 use crate::input;
+use crate::level_pack;
+use crate::level_pack::LevelSection;
 use crate::ui_bundle::UiBundle;
 use crate::vector2::Vector2;
 use conrod_core::color::Colorable;
@@ -17,15 +19,16 @@ use piston_window::*;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-pub struct MenuScreen<'a> {
-    pub world_list: Rc<Vec<&'a str>>,
+pub struct MenuScreen {
+    pub level_sections: Rc<Vec<LevelSection>>,
+    // Counts through the levels of every section in order.
     pub selected_world_index: usize,
     pub fps_counter: FpsCounter,
     pub image_map: conrod_core::image::Map<G2dTexture>,
     pub logo_image_id: Id, // todo: remove
 }
 
-impl<'a> GameState for MenuScreen<'a> {
+impl GameState for MenuScreen {
     fn render(&mut self, c: Context, gl: &mut G2d, ui_bundle: &mut UiBundle) {
         self.fps_counter.calculate_fps();
 
@@ -45,13 +48,14 @@ impl<'a> GameState for MenuScreen<'a> {
             self.selected_world_index -= 1;
         }
 
-        if game_state_utils::did_press_key(&key_states, Key::Down) && self.selected_world_index < self.world_list.len() - 1 {
+        let num_levels = level_pack::count_levels(&self.level_sections);
+        if game_state_utils::did_press_key(&key_states, Key::Down) && self.selected_world_index + 1 < num_levels {
             self.selected_world_index += 1;
         }
 
         self.update_ui(ui_bundle);
 
-        if game_state_utils::did_click(&mouse_states) || game_state_utils::did_press_key(&key_states, Key::Return) {
+        if num_levels > 0 && (game_state_utils::did_click(&mouse_states) || game_state_utils::did_press_key(&key_states, Key::Return)) {
             UpdateResult {
                 result_type: UpdateResultType::Success,
                 result_code: self.selected_world_index as i64,
@@ -66,9 +70,11 @@ impl<'a> GameState for MenuScreen<'a> {
     }
 }
 
-impl<'a> MenuScreen<'a> {
+impl MenuScreen {
     fn update_ui(&self, ui_bundle: &mut UiBundle) {
-        ui_bundle.ids.world_list.resize(self.world_list.len(), &mut ui_bundle.conrod_ui.widget_id_generator());
+        let num_levels = level_pack::count_levels(&self.level_sections);
+        ui_bundle.ids.world_list.resize(num_levels, &mut ui_bundle.conrod_ui.widget_id_generator());
+        ui_bundle.ids.world_list_headers.resize(self.level_sections.len(), &mut ui_bundle.conrod_ui.widget_id_generator());
 
         let mut ui_cell = ui_bundle.conrod_ui.set_widgets();
 
@@ -76,20 +82,34 @@ impl<'a> MenuScreen<'a> {
         conrod_core::widget::Text::new("WELCOME TO GUNGUN WARRIORS").font_size(36).color(conrod_core::color::WHITE).mid_top_of(ui_bundle.ids.canvas).set(ui_bundle.ids.title, &mut ui_cell);
 
         let mut id_widget_above = ui_bundle.ids.title;
-        for i in 0..self.world_list.len() {
-            let color = if i == self.selected_world_index {
-                conrod_core::color::BLUE
-            } else {
-                conrod_core::color::WHITE
-            };
-
-            conrod_core::widget::Text::new(self.world_list[i])
-                .font_size(36)
-                .color(color)
-                .down_from(id_widget_above, 5.0)
+        let mut i = 0;
+        for (section_index, section) in self.level_sections.iter().enumerate() {
+            conrod_core::widget::Text::new(section.title.as_str())
+                .font_size(24)
+                .color(conrod_core::color::LIGHT_GREY)
+                .down_from(id_widget_above, 20.0)
                 .align_middle_x_of(ui_bundle.ids.canvas)
-                .set(ui_bundle.ids.world_list[i], &mut ui_cell);
-            id_widget_above = ui_bundle.ids.world_list[i];
+                .set(ui_bundle.ids.world_list_headers[section_index], &mut ui_cell);
+            id_widget_above = ui_bundle.ids.world_list_headers[section_index];
+
+            for listing in &section.levels {
+                let color = if i == self.selected_world_index {
+                    conrod_core::color::BLUE
+                } else if listing.error.is_some() {
+                    conrod_core::color::RED
+                } else {
+                    conrod_core::color::WHITE
+                };
+
+                conrod_core::widget::Text::new(listing.title.as_str())
+                    .font_size(36)
+                    .color(color)
+                    .down_from(id_widget_above, 5.0)
+                    .align_middle_x_of(ui_bundle.ids.canvas)
+                    .set(ui_bundle.ids.world_list[i], &mut ui_cell);
+                id_widget_above = ui_bundle.ids.world_list[i];
+                i += 1;
+            }
         }
 
         self.fps_counter.update_ui(&mut ui_cell, &ui_bundle.ids);
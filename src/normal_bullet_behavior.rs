@@ -0,0 +1,10 @@
+use crate::bullet_behavior::BulletBehavior;
+
+// Does whatever `collision_config` says for bullets.
+pub struct NormalBulletBehavior;
+
+impl BulletBehavior for NormalBulletBehavior {
+    fn name(&self) -> &str {
+        "normal"
+    }
+}
@@ -1,11 +1,60 @@
-#[derive(Copy, Clone, PartialEq, Eq)]
+use crate::serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ObjectType {
     Wall,
+    // A wall that comes down after enough hits.
+    BreakableWall,
+    // A wall that stops guns but lets bullets through.
+    GlassWall,
+    // A wall that guns bounce off.
+    BumperWall,
     Bullet,
-    HandGun,
-    GunAxe,
-    ShotGun,
+    Gun,
+    // A gun that kills enemies it hits.
+    LethalGun,
     Enemy,
+    // A bullet fired by a turret enemy.
+    EnemyBullet,
     Player,
+    // Someone who mustn't be shot.
+    Hostage,
+    // A wall that a switch can take away and put back.
+    Door,
+    // Opens and closes doors when it's hit.
+    Switch,
     Ground,
 }
+
+impl ObjectType {
+    // The name the config uses for it.
+    pub fn name(self) -> &'static str {
+        match self {
+            ObjectType::Wall => "wall",
+            ObjectType::BreakableWall => "breakable_wall",
+            ObjectType::GlassWall => "glass_wall",
+            ObjectType::BumperWall => "bumper_wall",
+            ObjectType::Bullet => "bullet",
+            ObjectType::Gun => "gun",
+            ObjectType::LethalGun => "lethal_gun",
+            ObjectType::Enemy => "enemy",
+            ObjectType::EnemyBullet => "enemy_bullet",
+            ObjectType::Player => "player",
+            ObjectType::Hostage => "hostage",
+            ObjectType::Door => "door",
+            ObjectType::Switch => "switch",
+            ObjectType::Ground => "ground",
+        }
+    }
+
+    // Whether bullets that bounce or split off walls do so off this too.
+    pub fn is_wall(self) -> bool {
+        matches!(self, ObjectType::Wall | ObjectType::BreakableWall | ObjectType::BumperWall | ObjectType::Door)
+    }
+
+    // Things that are only ever where the level put them, so they can't be stopped or bounced.
+    pub fn never_moves(self) -> bool {
+        matches!(self, ObjectType::Wall | ObjectType::BreakableWall | ObjectType::GlassWall | ObjectType::BumperWall | ObjectType::Player | ObjectType::Hostage | ObjectType::Door | ObjectType::Switch)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::enemy_behavior::EnemyBehavior;
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
+
+pub struct PatrolEnemyBehavior {
+    // The middles of the path's cells. The enemy loops back to the first once it reaches the last.
+    pub path: Vec<Vector2>,
+    pub speed: f64,
+    pub next_waypoint: usize,
+}
+
+impl EnemyBehavior for PatrolEnemyBehavior {
+    fn is_static(&self) -> bool {
+        false
+    }
+
+    // Lands exactly on each waypoint rather than overshooting it.
+    fn velocity(&mut self, position: &Vector2, _player_position: &Vector2, dt: f64) -> Vector2 {
+        let to_waypoint = self.path[self.next_waypoint] - *position;
+        let distance = to_waypoint.magnitude();
+        if distance <= self.speed * dt {
+            self.next_waypoint = (self.next_waypoint + 1) % self.path.len();
+            return to_waypoint / dt;
+        }
+        to_waypoint.normalized() * self.speed
+    }
+
+    fn act(&mut self, _position: &Vector2, _player_position: &Vector2, _dt: f64) -> Vec<WorldReq> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,32 @@
+use crate::bullet::Bullet;
+use crate::bullet_behavior::BulletBehavior;
+use crate::collidable::Collision;
+use crate::config::CollisionConfig;
+use crate::config::CollisionResponse;
+use crate::object_type::ObjectType;
+use crate::simulation::WorldReq;
+
+// Enemies still die to it as the table says, but it keeps going.
+pub struct PiercingBulletBehavior;
+
+impl BulletBehavior for PiercingBulletBehavior {
+    fn name(&self) -> &str {
+        "piercing"
+    }
+
+    fn collision_response(&self, _bullet: &Bullet, other_object_type: ObjectType, collision_config: &CollisionConfig) -> CollisionResponse {
+        if other_object_type == ObjectType::Enemy {
+            CollisionResponse::Ignore
+        } else {
+            collision_config.response(ObjectType::Bullet, other_object_type)
+        }
+    }
+
+    // It overlaps an enemy for a few ticks on the way through, which would otherwise hit it again on each.
+    fn collide(&self, bullet: &mut Bullet, collision: &Collision) -> Vec<WorldReq> {
+        if collision.other_object_type == ObjectType::Enemy {
+            bullet.pierced.push(collision.other_handle);
+        }
+        Vec::new()
+    }
+}
@@ -1,3 +1,7 @@
+use crate::collidable::Collidable;
+use crate::collidable::Collision;
+use crate::collidable_object::CollidableObject;
+use crate::config::CollisionResponse;
 use crate::game_object::GameObject;
 use crate::input;
 use crate::gun_concept::GunConcept;
@@ -6,7 +10,7 @@ use crate::renderable::Renderable;
 use crate::renderable_object::RenderableObject;
 use crate::updatable::Updatable;
 use crate::vector2::Vector2;
-use crate::world::WorldReq;
+use crate::simulation::WorldReq;
 use piston_window::Key;
 use piston_window::MouseButton;
 use piston_window::UpdateArgs;
@@ -22,7 +26,10 @@ pub struct Player {
     pub selected_renderable_object: RenderableObject,
     pub gun_concepts: Vec<Rc<RefCell<GunConcept>>>,
     pub current_gun_concept_index: usize,
+    pub collidable_object: CollidableObject,
     pub is_visible: bool,
+    // Something the collision table says destroys the player ran into it, which loses the level.
+    pub was_hit: bool,
 }
 
 impl GameObject for Player {
@@ -78,6 +85,28 @@ impl Updatable for Player {
     }
 }
 
+impl Collidable for Player {
+    fn get_collidable_object(&self) -> &CollidableObject {
+        &self.collidable_object
+    }
+
+    // The player turns to face the mouse but never moves.
+    fn get_velocity(&self) -> Vector2 {
+        Vector2::default()
+    }
+
+    fn set_velocity(&mut self, _velocity: Vector2) {}
+
+    fn set_position(&mut self, _position: Vector2) {}
+
+    fn collide(&mut self, collision: &Collision) -> Vec<WorldReq> {
+        if collision.response == CollisionResponse::Destroy {
+            self.was_hit = true;
+        }
+        Vec::new()
+    }
+}
+
 impl Player {
     fn gun_concept_mut(&self) -> RefMut<GunConcept> {
         self.gun_concepts[self.current_gun_concept_index].borrow_mut()
@@ -107,7 +136,12 @@ impl Player {
     fn apply_input(&mut self, key_states: &HashMap<Key, input::ButtonState>, mouse_states: &HashMap<MouseButton, input::ButtonState>, mouse_pos: &Vector2, dt: f64) -> Vec<WorldReq> {
         let mut world_reqs: Vec<WorldReq> = Vec::new();
 
-        for (button, value) in mouse_states {
+        // Walk the buttons in a fixed order rather than the maps' hash order so the same input always plays out the same way.
+        for button in &[MouseButton::Left, MouseButton::Right] {
+            let value = match mouse_states.get(button) {
+                Some(value) => value,
+                None => continue,
+            };
             match *button {
                 MouseButton::Left => {
                     if value.pressed {
@@ -126,7 +160,11 @@ impl Player {
             }
         }
 
-        for (key, value) in key_states {
+        for key in &[Key::Q, Key::E] {
+            let value = match key_states.get(key) {
+                Some(value) => value,
+                None => continue,
+            };
             match *key {
                 Key::Q => {
                     if value.pressed {
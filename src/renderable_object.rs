@@ -1,6 +1,5 @@
-use piston_window::*;
-use std::rc::Rc;
+use crate::texture_handle::TextureHandle;
 
 pub struct RenderableObject {
-    pub texture: Rc<G2dTexture>,
+    pub texture: TextureHandle,
 }
@@ -0,0 +1,85 @@
+use crate::serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::path::Path;
+use std::path::PathBuf;
+
+use piston_window::Key;
+use piston_window::MouseButton;
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::input;
+use crate::vector2::Vector2;
+
+// Bump this whenever a change to the simulation or the file layout would make old replays play out differently.
+pub const REPLAY_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub level_name: String,
+    pub config: Config,
+    pub ticks: Vec<ReplayTick>,
+}
+
+// The input the simulation saw on one tick.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ReplayTick {
+    pub key_states: HashMap<Key, input::ButtonState>,
+    pub mouse_states: HashMap<MouseButton, input::ButtonState>,
+    pub mouse_pos: Vector2,
+}
+
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub next_tick_index: usize,
+}
+
+impl ReplayPlayback {
+    // Once the recording runs out the simulation just keeps going with nothing pressed.
+    pub fn next_tick(&mut self) -> ReplayTick {
+        let tick = self.replay.ticks.get(self.next_tick_index).cloned().unwrap_or_default();
+        self.next_tick_index += 1;
+        tick
+    }
+}
+
+fn replay_error(path: &Path, message: String) -> Error {
+    Error::Replay {
+        path: path.display().to_string(),
+        message,
+    }
+}
+
+pub fn read_replay_from_file<P: AsRef<Path>>(path: P) -> Result<Replay, Error> {
+    let path_ref = path.as_ref();
+
+    let file = File::open(path_ref).map_err(|err| replay_error(path_ref, format!("couldn't be opened: {}", err)))?;
+    let reader = BufReader::new(file);
+    let replay: Replay = serde_json::from_reader(reader).map_err(|err| replay_error(path_ref, format!("doesn't parse: {}", err)))?;
+
+    if replay.version != REPLAY_VERSION {
+        return Err(replay_error(path_ref, format!("is version {}, but this build plays version {}", replay.version, REPLAY_VERSION)));
+    }
+
+    Ok(replay)
+}
+
+// Writes the replay into `directory`, named after the level and the time, and returns where it went.
+pub fn write_replay_to_file(replay: &Replay, directory: &Path) -> Result<PathBuf, Error> {
+    fs::create_dir_all(directory).map_err(|err| replay_error(directory, format!("directory couldn't be created: {}", err)))?;
+
+    // Custom levels are named by their path, so only keep the file name part.
+    let level_file_stem = Path::new(&replay.level_name).file_stem().map_or_else(|| replay.level_name.clone(), |file_stem| file_stem.to_string_lossy().into_owned());
+    let file_name = format!("{}-{}.json", level_file_stem, time::now().strftime("%Y%m%d-%H%M%S").unwrap());
+    let path = directory.join(file_name);
+
+    let file = File::create(&path).map_err(|err| replay_error(&path, format!("couldn't be created: {}", err)))?;
+    serde_json::to_writer(BufWriter::new(file), replay).map_err(|err| replay_error(&path, format!("couldn't be written: {}", err)))?;
+
+    Ok(path)
+}
@@ -0,0 +1,35 @@
+use crate::bullet::Bullet;
+use crate::bullet_behavior::BulletBehavior;
+use crate::collidable::Collision;
+use crate::config::CollisionConfig;
+use crate::config::CollisionResponse;
+use crate::object_type::ObjectType;
+use crate::simulation::WorldReq;
+
+pub struct RicochetBulletBehavior {
+    pub max_bounces: u32,
+    // The fraction of its speed a bullet loses each bounce.
+    pub speed_loss_per_bounce: f64,
+}
+
+impl BulletBehavior for RicochetBulletBehavior {
+    fn name(&self) -> &str {
+        "ricochet"
+    }
+
+    fn collision_response(&self, bullet: &Bullet, other_object_type: ObjectType, collision_config: &CollisionConfig) -> CollisionResponse {
+        if other_object_type.is_wall() && bullet.bounces < self.max_bounces {
+            CollisionResponse::Bounce
+        } else {
+            collision_config.response(ObjectType::Bullet, other_object_type)
+        }
+    }
+
+    fn collide(&self, bullet: &mut Bullet, collision: &Collision) -> Vec<WorldReq> {
+        if collision.response == CollisionResponse::Bounce {
+            bullet.bounces += 1;
+            bullet.velocity *= 1.0 - self.speed_loss_per_bounce;
+        }
+        Vec::new()
+    }
+}
@@ -0,0 +1,699 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::rc::Rc;
+
+use nalgebra;
+use nalgebra::geometry::Isometry2;
+use nalgebra::geometry::Point2;
+use ncollide2d::bounding_volume::aabb::AABB;
+use ncollide2d::bounding_volume::BoundingVolume;
+use ncollide2d::query;
+use ncollide2d::query::Proximity;
+use ncollide2d::shape::Ball;
+use ncollide2d::shape::ConvexPolygon;
+use ncollide2d::shape::Cuboid;
+use ncollide2d::shape::Shape;
+use piston_window::Key;
+use piston_window::MouseButton;
+use piston_window::UpdateArgs;
+
+use crate::collidable::Collidable;
+use crate::collidable::CollidableHandle;
+use crate::collidable::Collision;
+use crate::collision_grid::CollisionGrid;
+use crate::config::ColliderShape;
+use crate::config::CollisionConfig;
+use crate::config::CollisionResponse;
+use crate::config::TuningConfig;
+use crate::door::Door;
+use crate::enemy::Enemy;
+use crate::game_object::GameObject;
+use crate::hostage::Hostage;
+use crate::input;
+use crate::object_type::ObjectType;
+use crate::player::Player;
+use crate::renderable::Renderable;
+use crate::updatable::Updatable;
+use crate::vector2::*;
+use crate::wall::Wall;
+
+pub const ENEMY_LAYER: usize = 1;
+pub const PROJECTILE_LAYER: usize = 2;
+// With the walls, over the ground.
+const DOOR_LAYER: usize = 0;
+
+// How far apart, in pixels, two things may be and still count as touching. Contacts are worked out from half this
+// far before the time of impact.
+const CONTACT_PREDICTION: f64 = 1.0;
+
+pub struct GameEndedState {
+    pub game_ended: bool,
+    pub won: bool,
+}
+
+// TODO: Split DynamicRenderable into Updatable and Collidable
+pub enum WorldRequestType {
+    AddUpdatable,
+    AddDynamicRenderable,
+    PlaySound,
+    Explode,
+}
+
+// Hits everything within `radius` of `position` like a bullet would.
+pub struct Explosion {
+    pub position: Vector2,
+    pub radius: f64,
+    // What set it off, for enemy armor. See `Collidable::kind_names`.
+    pub kind_names: Vec<String>,
+}
+
+pub struct WorldReq {
+    pub renderable: Option<Rc<RefCell<Renderable>>>,
+    pub updatable: Option<Rc<RefCell<Updatable>>>,
+    pub collidable: Option<Rc<RefCell<Collidable>>>,
+    pub sound: Option<String>,
+    pub explosion: Option<Explosion>,
+    pub req_type: WorldRequestType,
+}
+
+impl WorldReq {
+    pub fn play_sound(sound_name: &str) -> WorldReq {
+        WorldReq {
+            renderable: None,
+            updatable: None,
+            collidable: None,
+            sound: Some(sound_name.to_string()),
+            explosion: None,
+            req_type: WorldRequestType::PlaySound,
+        }
+    }
+
+    pub fn explode(explosion: Explosion) -> WorldReq {
+        WorldReq {
+            renderable: None,
+            updatable: None,
+            collidable: None,
+            sound: None,
+            explosion: Some(explosion),
+            req_type: WorldRequestType::Explode,
+        }
+    }
+}
+
+// A collidable and the handle others are told about when they hit it.
+pub type CollidableEntry = (CollidableHandle, Rc<RefCell<Collidable>>);
+
+// Everything about a level that can be stepped without a window: positions, velocities,
+// colliders, gun chains and the win/loss state. The renderer and HUD only read from it.
+pub struct Simulation {
+    pub renderables: Vec<Vec<Rc<RefCell<Renderable>>>>,
+    // doesn't need to be a refcell but how do we make it not???????
+    pub moving_collidables: Vec<CollidableEntry>,
+    // Walls and stationary enemies never move, so they go in the grid once, when they're added.
+    pub static_collidables: CollisionGrid<CollidableEntry>,
+    next_collidable_handle: usize,
+    pub updatables: Vec<Rc<RefCell<Updatable>>>,
+    // Also in the lists above; kept here too for the HUD.
+    pub enemies: Vec<Rc<RefCell<Enemy>>>,
+    pub hostages: Vec<Rc<RefCell<Hostage>>>,
+    // Kept here too so the solver can tell how far gone they are.
+    pub walls: Vec<Rc<RefCell<Wall>>>,
+    // Open or closed, so closed ones can be put back.
+    pub doors: Vec<Rc<RefCell<Door>>>,
+    pub game_ended_state: GameEndedState,
+    pub player: Rc<RefCell<Player>>,
+    // Sounds requested during the last update, for whoever is presenting the simulation to play.
+    pub sounds_to_play: Vec<String>,
+    // The config's tuning with the level's overrides applied.
+    pub tuning: TuningConfig,
+    pub collision_config: CollisionConfig,
+}
+
+impl Simulation {
+    pub fn new(player: Rc<RefCell<Player>>, tuning: TuningConfig, collision_config: CollisionConfig) -> Simulation {
+        Simulation {
+            renderables: Vec::new(),
+            moving_collidables: Vec::new(),
+            static_collidables: CollisionGrid::new(f64::from(tuning.cell_width()), f64::from(tuning.cell_height())),
+            next_collidable_handle: 0,
+            updatables: Vec::new(),
+            enemies: Vec::new(),
+            hostages: Vec::new(),
+            walls: Vec::new(),
+            doors: Vec::new(),
+            game_ended_state: GameEndedState {
+                game_ended: false,
+                won: false,
+            },
+            player,
+            sounds_to_play: Vec::new(),
+            tuning,
+            collision_config,
+        }
+    }
+
+    pub fn add_renderable_at_layer(&mut self, renderable: Rc<RefCell<Renderable>>, layer: usize) {
+        while self.renderables.len() <= layer {
+            self.renderables.push(Vec::new());
+        }
+        self.renderables[layer].push(renderable);
+    }
+
+    pub fn add_collidable(&mut self, collidable: Rc<RefCell<Collidable>>) -> CollidableHandle {
+        let handle = CollidableHandle(self.next_collidable_handle);
+        self.next_collidable_handle += 1;
+        if collidable.borrow().is_static() {
+            let aabb = current_aabb(collidable.borrow().deref());
+            self.static_collidables.insert(aabb, (handle, collidable));
+        } else {
+            self.moving_collidables.push((handle, collidable));
+        }
+        handle
+    }
+
+    pub fn add_updatable(&mut self, updatable: Rc<RefCell<Updatable>>) {
+        self.updatables.push(updatable);
+    }
+
+    // Only enemies have to be cleared; hostages are left standing.
+    pub fn is_victorious(&self) -> bool {
+        if self.renderables.len() <= ENEMY_LAYER {
+            return true;
+        }
+
+        for renderable in &self.renderables[ENEMY_LAYER] {
+            if renderable.borrow().get_object_type() == ObjectType::Enemy {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn can_take_action(&self) -> bool {
+        let mut can_take_action = self.player.borrow().can_shoot_gun();
+        can_take_action = can_take_action || self.player.borrow().can_shoot_bullet();
+        can_take_action
+    }
+
+    // The player or a hostage was hit, which loses the level even if the same shot took out the last enemy.
+    fn was_hit(&self) -> bool {
+        self.player.borrow().was_hit || self.hostages.iter().any(|hostage| hostage.borrow().was_hit)
+    }
+
+    pub fn was_defeated(&self) -> bool {
+        if self.was_hit() {
+            return true;
+        }
+
+        if self.can_take_action() {
+            return false;
+        }
+
+        // Bullets and lethal guns still flying might yet hit something. Ones that were stopped won't.
+        !self.moving_collidables.iter().any(|(_, collidable)| {
+            let collidable = collidable.borrow();
+            let object_type = collidable.get_object_type();
+            (object_type == ObjectType::Bullet || object_type == ObjectType::LethalGun) && collidable.get_velocity() != Vector2::default()
+        })
+    }
+
+    pub fn update(&mut self, key_states: &HashMap<Key, input::ButtonState>, mouse_states: &HashMap<MouseButton, input::ButtonState>, mouse_pos: &Vector2, args: UpdateArgs) {
+        if self.game_ended_state.game_ended {
+            return;
+        }
+
+        if !self.was_hit() && self.is_victorious() {
+            self.game_ended_state = GameEndedState {
+                game_ended: true,
+                won: true,
+            };
+            return;
+        }
+
+        if self.was_defeated() {
+            self.game_ended_state = GameEndedState {
+                game_ended: true,
+                won: false,
+            };
+            return;
+        }
+
+        // Static things never move, so they can't run into each other and only pairs with something moving need testing,
+        // and only against things in the grid cells they passed through. Moving things are swept along the path they
+        // took last tick, so a fast bullet can't skip over a wall tile.
+        let swept_aabbs: Vec<AABB<f64>> = self.moving_collidables.iter().map(|(_, collidable)| swept_aabb(collidable.borrow().deref())).collect();
+        let mut moving_grid = CollisionGrid::new(f64::from(self.tuning.cell_width()), f64::from(self.tuning.cell_height()));
+        for (aabb, entry) in swept_aabbs.iter().zip(self.moving_collidables.iter()) {
+            moving_grid.insert(aabb.clone(), entry.clone());
+        }
+
+        let mut world_reqs: Vec<WorldReq> = Vec::new();
+        for (collidable1_index, (handle1, collidable1)) in self.moving_collidables.iter().enumerate() {
+            let object_type1 = collidable1.borrow().get_object_type();
+            let swept_aabb1 = &swept_aabbs[collidable1_index];
+            let nearby_static = self.static_collidables.query(swept_aabb1).into_iter();
+            // Pairs of moving things are tested once, from whichever was added first. Handles go up as things are added.
+            let nearby_moving = moving_grid.query(swept_aabb1).into_iter().filter(|(_, (handle2, _))| handle2.0 > handle1.0);
+
+            let mut hits: Vec<(f64, &CollidableEntry)> = Vec::new();
+            for (aabb2, entry2) in nearby_static.chain(nearby_moving) {
+                let (handle2, collidable2) = entry2;
+                if collidable1.borrow().passes_through(*handle2) || collidable2.borrow().passes_through(*handle1) {
+                    continue;
+                }
+                if !swept_aabb1.intersects(aabb2) || !reacts(collidable1.borrow().deref(), collidable2.borrow().deref(), &self.collision_config) {
+                    continue;
+                }
+
+                if let Some(time_of_impact) = swept_time_of_impact(collidable1.borrow().deref(), collidable2.borrow().deref()) {
+                    hits.push((time_of_impact, entry2));
+                }
+            }
+
+            // Only the first thing along the path gets hit if it changes the course of whatever was moving.
+            hits.sort_by(|(time1, _), (time2, _)| time1.partial_cmp(time2).unwrap_or(Ordering::Equal));
+            for (time_of_impact, (handle2, collidable2)) in hits {
+                let contact_time = time_just_before(collidable1.borrow().deref(), collidable2.borrow().deref(), time_of_impact);
+                let (mut collision1, mut collision2) = collisions_at_impact(*handle1, collidable1.borrow().deref(), *handle2, collidable2.borrow().deref(), contact_time);
+                let response1 = collidable1.borrow().collision_response(collision1.other_object_type, &self.collision_config);
+                let response2 = collidable2.borrow().collision_response(object_type1, &self.collision_config);
+                collision1.response = respond(collidable1.borrow_mut().deref_mut(), response1, &collision1, contact_time);
+                collision2.response = respond(collidable2.borrow_mut().deref_mut(), response2, &collision2, contact_time);
+                world_reqs.append(&mut collidable1.borrow_mut().collide(&collision1));
+                world_reqs.append(&mut collidable2.borrow_mut().collide(&collision2));
+                if collision1.response != CollisionResponse::Ignore {
+                    break;
+                }
+            }
+        }
+
+        self.handle_world_reqs(world_reqs);
+        self.put_back_closed_doors();
+
+        for renderable_layer in &mut self.renderables {
+            renderable_layer.retain(|ref renderable| is_kept(renderable.borrow().deref()));
+        }
+
+        self.updatables.retain(|ref updatable| is_kept(updatable.borrow().deref()));
+        self.enemies.retain(|enemy| !enemy.borrow().should_delete);
+        self.walls.retain(|wall| !wall.borrow().should_delete);
+
+        self.moving_collidables.retain(|(_, collidable)| is_kept(collidable.borrow().deref()));
+        self.static_collidables.retain(|(_, collidable)| is_kept(collidable.borrow().deref()));
+
+        let mut world_reqs: Vec<WorldReq> = Vec::new();
+        for updatable in &self.updatables {
+            let current_world_reqs = &mut updatable.borrow_mut().update(&key_states, &mouse_states, &mouse_pos, args);
+            world_reqs.append(current_world_reqs);
+        }
+        self.handle_world_reqs(world_reqs);
+    }
+
+    // Doors switched open this tick are taken out with everything else that should be deleted; ones switched closed
+    // go back in here.
+    fn put_back_closed_doors(&mut self) {
+        // A door the collision table lets something destroy is gone for good, open or closed.
+        self.doors.retain(|door| !door.borrow().should_delete);
+        let doors = self.doors.clone();
+        for door in doors {
+            let (is_in_world, is_added) = {
+                let door = door.borrow();
+                (door.is_in_world(), door.is_added)
+            };
+            if is_in_world && !is_added {
+                self.add_renderable_at_layer(door.clone(), DOOR_LAYER);
+                self.add_collidable(door.clone());
+            }
+            // Open ones are taken out with everything else that's left the world, below.
+            door.borrow_mut().is_added = is_in_world;
+        }
+    }
+
+    fn handle_world_reqs(&mut self, world_reqs: Vec<WorldReq>) {
+        for world_req in world_reqs {
+            match world_req.req_type {
+                WorldRequestType::AddDynamicRenderable => {
+                    assert!(world_req.renderable.is_some());
+                    assert!(world_req.collidable.is_some());
+                    if let Some(renderable) = world_req.renderable {
+                        self.add_renderable_at_layer(renderable, PROJECTILE_LAYER);
+                    }
+                    if let Some(collidable) = world_req.collidable {
+                        self.add_collidable(collidable);
+                    }
+                }
+                WorldRequestType::AddUpdatable => {
+                    assert!(world_req.updatable.is_some());
+                    if let Some(updatable) = world_req.updatable {
+                        self.add_updatable(updatable);
+                    }
+                }
+                WorldRequestType::PlaySound => {
+                    assert!(world_req.sound.is_some());
+                    if let Some(sound) = world_req.sound {
+                        self.sounds_to_play.push(sound);
+                    }
+                }
+                WorldRequestType::Explode => {
+                    assert!(world_req.explosion.is_some());
+                    if let Some(explosion) = world_req.explosion {
+                        self.explode(&explosion);
+                    }
+                }
+            }
+        }
+    }
+
+    fn explode(&self, explosion: &Explosion) {
+        let blast = Ball::new(explosion.radius);
+        let blast_isometry = Isometry2::new(nalgebra::core::Vector2::new(explosion.position.x, explosion.position.y), 0.0);
+        let blast_aabb = blast.aabb(&blast_isometry);
+        let nearby_static = self.static_collidables.query(&blast_aabb).into_iter().map(|(_, entry)| entry);
+        for (_, collidable) in nearby_static.chain(self.moving_collidables.iter()) {
+            let caught = {
+                let collidable = collidable.borrow();
+                collidable.collision_response(ObjectType::Bullet, &self.collision_config) == CollisionResponse::Destroy
+                    && with_collider_shape(collidable.deref(), |shape| {
+                        query::proximity(&blast_isometry, &blast, &collider_isometry(collidable.deref(), collidable.get_position()), shape, 0.0) == Proximity::Intersecting
+                    })
+            };
+            if caught {
+                let mut collidable = collidable.borrow_mut();
+                let direction_to_blast = (explosion.position - *collidable.get_position()).normalized();
+                if !collidable.survives_hit(direction_to_blast, &explosion.kind_names) {
+                    collidable.set_should_delete(true);
+                }
+            }
+        }
+    }
+}
+
+fn collides(collidable1: &Collidable, collidable2: &Collidable) -> bool {
+    with_collider_shape(collidable1, |shape1| {
+        with_collider_shape(collidable2, |shape2| {
+            query::proximity(&collider_isometry(collidable1, collidable1.get_position()), shape1, &collider_isometry(collidable2, collidable2.get_position()), shape2, 0.0) == Proximity::Intersecting
+        })
+    })
+}
+
+// When, as a fraction of the last tick, two collidables first touched, if they did. Both are swept from where they were
+// at the start of the tick to where they are now, turned the way they are now.
+fn swept_time_of_impact(collidable1: &Collidable, collidable2: &Collidable) -> Option<f64> {
+    let motion1 = *collidable1.get_position() - *collidable1.get_previous_position();
+    let motion2 = *collidable2.get_position() - *collidable2.get_previous_position();
+    let relative_motion = motion1 - motion2;
+    if relative_motion.x == 0.0 && relative_motion.y == 0.0 {
+        return if collides(collidable1, collidable2) { Some(0.0) } else { None };
+    }
+
+    with_collider_shape(collidable1, |shape1| {
+        with_collider_shape(collidable2, |shape2| {
+            query::time_of_impact(
+                &collider_isometry(collidable1, collidable1.get_previous_position()),
+                &nalgebra::core::Vector2::new(motion1.x, motion1.y),
+                shape1,
+                &collider_isometry(collidable2, collidable2.get_previous_position()),
+                &nalgebra::core::Vector2::new(motion2.x, motion2.y),
+                shape2,
+            )
+        })
+    })
+    .filter(|time_of_impact| *time_of_impact <= 1.0)
+}
+
+// A moment just before two collidables touch. Shapes caught exactly touching confuse ncollide's contact queries,
+// and things stopped or bounced there would stay stuck to each other.
+fn time_just_before(collidable1: &Collidable, collidable2: &Collidable, time_of_impact: f64) -> f64 {
+    let relative_motion = (*collidable1.get_position() - *collidable1.get_previous_position()) - (*collidable2.get_position() - *collidable2.get_previous_position());
+    let distance = relative_motion.magnitude();
+    if distance == 0.0 {
+        return time_of_impact;
+    }
+    (time_of_impact - CONTACT_PREDICTION / 2.0 / distance).max(0.0)
+}
+
+// Still in the world, and not about to be deleted from it.
+fn is_kept<T: GameObject + ?Sized>(game_object: &T) -> bool {
+    !game_object.get_should_delete() && game_object.is_in_world()
+}
+
+// Whether either of two collidables does anything when they run into each other.
+fn reacts(collidable1: &Collidable, collidable2: &Collidable, collision_config: &CollisionConfig) -> bool {
+    collidable1.collision_response(collidable2.get_object_type(), collision_config) != CollisionResponse::Ignore
+        || collidable2.collision_response(collidable1.get_object_type(), collision_config) != CollisionResponse::Ignore
+}
+
+// Applies a response to a collision. Returns what actually happened, which is `Ignore` if the collidable carries on
+// the way it was going.
+fn respond(collidable: &mut Collidable, response: CollisionResponse, collision: &Collision, contact_time: f64) -> CollisionResponse {
+    match response {
+        CollisionResponse::Destroy => {
+            if collidable.survives_hit(collision.normal * -1.0, &collision.other_kind_names) {
+                return CollisionResponse::Ignore;
+            }
+            collidable.set_should_delete(true);
+        }
+        CollisionResponse::Stop => {
+            let position = position_at(collidable, contact_time);
+            collidable.set_position(position);
+            collidable.set_velocity(Vector2::default());
+        }
+        CollisionResponse::Bounce => {
+            let velocity = collidable.get_velocity();
+            let speed_into_surface = velocity.dot(&collision.normal);
+            // Something already heading away, like after last tick's bounce, just carries on.
+            if speed_into_surface >= 0.0 {
+                return CollisionResponse::Ignore;
+            }
+            let position = position_at(collidable, contact_time);
+            collidable.set_position(position);
+            collidable.set_velocity(velocity - collision.normal * (2.0 * speed_into_surface));
+        }
+        CollisionResponse::Ignore => {}
+    }
+    response
+}
+
+// The contact between two collidables at `contact_time` into the last tick, as each of them sees it.
+fn collisions_at_impact(handle1: CollidableHandle, collidable1: &Collidable, handle2: CollidableHandle, collidable2: &Collidable, contact_time: f64) -> (Collision, Collision) {
+    let position1 = position_at(collidable1, contact_time);
+    let position2 = position_at(collidable2, contact_time);
+    let contact = with_collider_shape(collidable1, |shape1| {
+        with_collider_shape(collidable2, |shape2| query::contact(&collider_isometry(collidable1, &position1), shape1, &collider_isometry(collidable2, &position2), shape2, CONTACT_PREDICTION))
+    });
+    let (point1, point2, normal) = match contact {
+        Some(contact) => (
+            Vector2 {
+                x: contact.world1.x,
+                y: contact.world1.y,
+            },
+            Vector2 {
+                x: contact.world2.x,
+                y: contact.world2.y,
+            },
+            Vector2 {
+                x: contact.normal.x,
+                y: contact.normal.y,
+            },
+        ),
+        // Only when rounding leaves them just out of reach, so fall back on the line between their middles.
+        None => {
+            let middle = (position1 + position2) / 2.0;
+            (middle, middle, (position2 - position1).normalized())
+        }
+    };
+
+    // The contact's normal points from the first collidable to the second.
+    let collision1 = Collision {
+        other_handle: handle2,
+        other_object_type: collidable2.get_object_type(),
+        other_kind_names: collidable2.kind_names(),
+        point: point2,
+        normal: normal * -1.0,
+        response: CollisionResponse::Ignore,
+    };
+    let collision2 = Collision {
+        other_handle: handle1,
+        other_object_type: collidable1.get_object_type(),
+        other_kind_names: collidable1.kind_names(),
+        point: point1,
+        normal,
+        response: CollisionResponse::Ignore,
+    };
+    (collision1, collision2)
+}
+
+// Where the collidable was `time` of the way through the last tick.
+fn position_at(collidable: &Collidable, time: f64) -> Vector2 {
+    *collidable.get_previous_position() + (*collidable.get_position() - *collidable.get_previous_position()) * time
+}
+
+fn current_aabb(collidable: &Collidable) -> AABB<f64> {
+    with_collider_shape(collidable, |shape| shape.aabb(&collider_isometry(collidable, collidable.get_position())))
+}
+
+// Covers everywhere the collidable was during the last tick.
+fn swept_aabb(collidable: &Collidable) -> AABB<f64> {
+    with_collider_shape(collidable, |shape| {
+        let start = shape.aabb(&collider_isometry(collidable, collidable.get_previous_position()));
+        start.merged(&shape.aabb(&collider_isometry(collidable, collidable.get_position())))
+    })
+}
+
+fn collider_isometry(collidable: &Collidable, position: &Vector2) -> Isometry2<f64> {
+    Isometry2::new(nalgebra::core::Vector2::new(position.x, position.y), collidable.get_rotation())
+}
+
+// Builds the collidable's shape, scaled to its texture, and hands it to `query`. The shapes are cheap enough to
+// build for every test, and this way they always match the object's current scale.
+fn with_collider_shape<T, F: FnOnce(&Shape<f64>) -> T>(collidable: &Collidable, query: F) -> T {
+    let collidable_object = collidable.get_collidable_object();
+    let half_width = collidable_object.width * 0.5 * collidable.get_scale();
+    let half_height = collidable_object.height * 0.5 * collidable.get_scale();
+    let cuboid = || Cuboid::new(nalgebra::core::Vector2::new(half_width, half_height));
+    match &collidable_object.shape {
+        ColliderShape::Box => query(&cuboid()),
+        ColliderShape::Circle => query(&Ball::new(half_width.min(half_height))),
+        ColliderShape::Polygon { points } => {
+            let points: Vec<Point2<f64>> = points.iter().map(|point| Point2::new(point[0] * half_width, point[1] * half_height)).collect();
+            // Config validation rules out polygons without an inside, so this only guards against rounding.
+            match ConvexPolygon::try_from_points(&points) {
+                Some(polygon) => query(&polygon),
+                None => query(&cuboid()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use crate::config::Config;
+    use crate::headless;
+    use crate::headless_texture_registry::HeadlessTextureRegistry;
+    use crate::input::ButtonState;
+    use crate::level_document::LevelDocument;
+    use crate::level_loader;
+    use crate::vfs::Vfs;
+
+    const MAX_TICKS: usize = 1200;
+
+    // The default config, with guns that fly straight so shots go where they're aimed.
+    fn test_config() -> Config {
+        let mut config = config::read_default_config().unwrap();
+        for gun_definition in config.gun_definitions.values_mut() {
+            gun_definition.spin_rate = 0.0;
+        }
+        config
+    }
+
+    // A 32 by 18 room with `middle` written into row 9 right after the player, and a hand gun with one volley.
+    fn build(middle: &str, enemies: serde_json::Value, config: &Config) -> Simulation {
+        let wall_row = "W".repeat(32);
+        let ground_row = format!("W{}W", "_".repeat(30));
+        let player_row = format!("W__P{}{}W", middle, "_".repeat(27 - middle.len()));
+        let mut tiles = vec![wall_row.clone()];
+        for row in 1..17 {
+            tiles.push(if row == 8 { player_row.clone() } else { ground_row.clone() });
+        }
+        tiles.push(wall_row);
+
+        let level: LevelDocument = serde_json::from_value(serde_json::json!({
+            "title": "simulation test",
+            "gun_concepts": [{ "kind": "hand_gun" }],
+            "tiles": tiles,
+            "enemies": enemies,
+        }))
+        .unwrap();
+        let mut texture_registry = HeadlessTextureRegistry {
+            vfs: Rc::new(Vfs::from_config(config).unwrap()),
+            sizes_by_filename: HashMap::new(),
+        };
+        level_loader::build_level(&mut texture_registry, &level, config).unwrap()
+    }
+
+    fn tick(simulation: &mut Simulation, config: &Config, click: Option<MouseButton>, mouse_pos: Vector2) {
+        let mut mouse_states = HashMap::new();
+        if let Some(button) = click {
+            mouse_states.insert(button, ButtonState { held: false, pressed: true, released: false });
+        }
+        simulation.update(&HashMap::new(), &mouse_states, &mouse_pos, headless::tick_args(config));
+    }
+
+    // Shoots a gun towards `direction` from the player, then fires its bullets once it's clear of the player.
+    fn shoot(simulation: &mut Simulation, config: &Config, direction: Vector2) -> Vector2 {
+        let mouse_pos = simulation.player.borrow().position + direction * 100.0;
+        tick(simulation, config, None, mouse_pos);
+        tick(simulation, config, Some(MouseButton::Left), mouse_pos);
+        for _ in 0..10 {
+            tick(simulation, config, None, mouse_pos);
+        }
+        tick(simulation, config, Some(MouseButton::Right), mouse_pos);
+        mouse_pos
+    }
+
+    fn run_until_ended(simulation: &mut Simulation, config: &Config, mouse_pos: Vector2) {
+        for _ in 0..MAX_TICKS {
+            if simulation.game_ended_state.game_ended {
+                return;
+            }
+            tick(simulation, config, None, mouse_pos);
+        }
+        panic!("the level was still going after {} ticks", MAX_TICKS);
+    }
+
+    #[test]
+    fn shooting_the_last_enemy_wins() {
+        let config = test_config();
+        let mut simulation = build("________E", serde_json::json!([]), &config);
+
+        let mouse_pos = shoot(&mut simulation, &config, Vector2 { x: 1.0, y: 0.0 });
+        run_until_ended(&mut simulation, &config, mouse_pos);
+
+        assert!(simulation.enemies.is_empty());
+        assert!(simulation.game_ended_state.won);
+    }
+
+    #[test]
+    fn an_enemy_reaching_the_player_loses() {
+        let config = test_config();
+        let mut simulation = build("__E", serde_json::json!([{ "row": 9, "column": 7, "behavior": { "kind": "chase" } }]), &config);
+
+        let mouse_pos = simulation.player.borrow().position + Vector2 { x: 0.0, y: -100.0 };
+        run_until_ended(&mut simulation, &config, mouse_pos);
+
+        assert!(simulation.player.borrow().was_hit);
+        assert!(!simulation.game_ended_state.won);
+    }
+
+    #[test]
+    fn running_out_of_shots_loses() {
+        let config = test_config();
+        let mut simulation = build("________E", serde_json::json!([]), &config);
+
+        // Straight up, nowhere near the enemy.
+        let mouse_pos = shoot(&mut simulation, &config, Vector2 { x: 0.0, y: -1.0 });
+        run_until_ended(&mut simulation, &config, mouse_pos);
+
+        assert_eq!(simulation.enemies.len(), 1);
+        assert!(!simulation.player.borrow().was_hit);
+        assert!(!simulation.game_ended_state.won);
+    }
+
+    #[test]
+    fn shooting_a_hostage_loses() {
+        let config = test_config();
+        let mut simulation = build("____C___E", serde_json::json!([]), &config);
+
+        let mouse_pos = shoot(&mut simulation, &config, Vector2 { x: 1.0, y: 0.0 });
+        run_until_ended(&mut simulation, &config, mouse_pos);
+
+        assert!(simulation.hostages[0].borrow().was_hit);
+        assert!(!simulation.game_ended_state.won);
+    }
+}
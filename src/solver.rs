@@ -0,0 +1,724 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use std::path::Path;
+use std::rc::Rc;
+
+use piston_window::Key;
+use piston_window::MouseButton;
+use piston_window::UpdateArgs;
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::game_object::GameObject;
+use crate::headless_texture_registry::HeadlessTextureRegistry;
+use crate::input::ButtonState;
+use crate::level_document;
+use crate::level_document::LevelDocument;
+use crate::level_loader;
+use crate::object_type::ObjectType;
+use crate::replay;
+use crate::replay::Replay;
+use crate::replay::ReplayTick;
+use crate::replay::REPLAY_VERSION;
+use crate::simulation::Simulation;
+use crate::vector2::Vector2;
+use crate::vfs::Vfs;
+
+// A click is pressed, held for a tick (the tommy gun fires on held) and released.
+const CLICK_TICKS: u32 = 3;
+// Bullets fire on the press, so their trigger is just pressed and released.
+const BULLET_CLICK_TICKS: u32 = 2;
+const AIM_DISTANCE: f64 = 100.0;
+
+// One gun concept's turn: aim, fire the first gun, chain more guns off it, then fire the bullets.
+#[derive(Clone)]
+pub struct GunPlan {
+    pub gun_concept_index: usize,
+    pub aim_angle: f64,
+    // Ticks between each gun shot and the next one chained off it.
+    pub chain_delays: Vec<u32>,
+    // Ticks between the last gun shot and the bullets.
+    pub bullet_delay: u32,
+    // Ticks between each volley of bullets and the next, for gun concepts with more than one.
+    pub volley_delays: Vec<u32>,
+}
+
+// Everything about a level a plan can change, so plans that leave it the same way are only searched on from once.
+type StateKey = Vec<i64>;
+
+// Somewhere the search has got to: the plans played so far, and the input that plays them on a fresh copy of the level.
+#[derive(Clone)]
+struct SearchState {
+    plans: Vec<GunPlan>,
+    ticks: Vec<ReplayTick>,
+    shots_taken: usize,
+    current_gun_concept_index: usize,
+    // The gun concepts that can still shoot a gun.
+    gun_concept_indices: Vec<usize>,
+}
+
+struct Search {
+    // In the order they were found, which is also fewest gun shots first.
+    states: Vec<SearchState>,
+    seen: HashSet<StateKey>,
+    won: Option<SearchState>,
+}
+
+struct PlanInput {
+    ticks: Vec<ReplayTick>,
+    // Ticks on which the plan expects the gun concept to still have guns in play.
+    needs_guns: Vec<usize>,
+}
+
+pub struct Solution {
+    pub plans: Vec<GunPlan>,
+    pub shots_taken: usize,
+    pub replay: Replay,
+}
+
+pub struct Solver {
+    level_name: String,
+    level: LevelDocument,
+    config: Config,
+    texture_registry: HeadlessTextureRegistry,
+    player_position: Vector2,
+    num_gun_concepts: usize,
+}
+
+impl Solver {
+    pub fn new(level_name: &str, config: Config) -> Result<Solver, Error> {
+        let vfs = Rc::new(Vfs::from_config(&config)?);
+        let level = level_document::read_level_document(&vfs, level_name)?;
+        Solver::with_level(level_name, level, config, vfs)
+    }
+
+    pub fn with_level(level_name: &str, level: LevelDocument, config: Config, vfs: Rc<Vfs>) -> Result<Solver, Error> {
+        // The search is only as finite as its steps, which can come from the command line as well as the config file.
+        let problems = config.solver_config.validate();
+        if !problems.is_empty() {
+            return Err(Error::MalformedConfig {
+                path: String::from("solver_config"),
+                message: problems.join("; "),
+            });
+        }
+
+        let mut solver = Solver {
+            level_name: String::from(level_name),
+            level,
+            config,
+            texture_registry: HeadlessTextureRegistry {
+                vfs,
+                sizes_by_filename: HashMap::new(),
+            },
+            player_position: Vector2::default(),
+            num_gun_concepts: 0,
+        };
+
+        let simulation = level_loader::build_level(&mut solver.texture_registry, &solver.level, &solver.config)?;
+        solver.player_position = simulation.player.borrow().position;
+        solver.num_gun_concepts = simulation.player.borrow().gun_concepts.len();
+        Ok(solver)
+    }
+
+    fn load(&mut self) -> Result<Simulation, Error> {
+        level_loader::build_level(&mut self.texture_registry, &self.level, &self.config)
+    }
+
+    fn seconds_to_ticks(&self, seconds: f64) -> u32 {
+        (seconds * f64::from(self.config.solver_config.ticks_per_second)).round() as u32
+    }
+
+    fn tick_args(&self) -> UpdateArgs {
+        UpdateArgs {
+            dt: 1.0 / f64::from(self.config.solver_config.ticks_per_second),
+        }
+    }
+
+    // Searches for the fewest gun shots that win. Each plan carries on from wherever the plans before it left the level,
+    // so the search sees what they really did together: walls broken, doors opened and enemies that moved.
+    pub fn solve(&mut self) -> Result<Option<Solution>, Error> {
+        let simulation = self.load()?;
+        let mut search = Search {
+            states: vec![SearchState {
+                plans: Vec::new(),
+                ticks: Vec::new(),
+                shots_taken: 0,
+                current_gun_concept_index: 0,
+                gun_concept_indices: usable_gun_concepts(&simulation),
+            }],
+            seen: HashSet::new(),
+            won: None,
+        };
+        search.seen.insert(state_key(&simulation));
+
+        // Every state found while searching one number of gun shots took exactly that many, so they're only searched
+        // on from with more.
+        for num_gun_shots in 1..=self.config.solver_config.max_gun_shots {
+            let num_states = search.states.len();
+            for state_index in 0..num_states {
+                let state = search.states[state_index].clone();
+                if state.shots_taken >= num_gun_shots {
+                    continue;
+                }
+
+                for &gun_concept_index in &state.gun_concept_indices {
+                    self.search_gun_concept(&state, gun_concept_index, num_gun_shots - state.shots_taken, &mut search)?;
+                    if let Some(won) = search.won.take() {
+                        return Ok(Some(self.solution(won)));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn search_gun_concept(&mut self, state: &SearchState, gun_concept_index: usize, num_gun_shots: usize, search: &mut Search) -> Result<(), Error> {
+        let angle_step = self.config.solver_config.angle_step_degrees.to_radians();
+        let num_angles = (2.0 * PI / angle_step).round().max(1.0) as usize;
+
+        for angle_index in 0..num_angles {
+            let plan = GunPlan {
+                gun_concept_index,
+                aim_angle: angle_index as f64 * angle_step,
+                chain_delays: Vec::new(),
+                bullet_delay: CLICK_TICKS,
+                volley_delays: Vec::new(),
+            };
+            self.search_chain(state, plan, num_gun_shots - 1, search)?;
+            if search.won.is_some() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn search_chain(&mut self, state: &SearchState, plan: GunPlan, num_chained_shots: usize, search: &mut Search) -> Result<(), Error> {
+        if num_chained_shots == 0 {
+            return self.search_bullet_delay(state, plan, search);
+        }
+
+        let chain_delay_step = self.seconds_to_ticks(self.config.solver_config.chain_delay_step_seconds).max(1);
+        let max_chain_delay = self.seconds_to_ticks(self.config.solver_config.max_chain_delay_seconds);
+
+        let mut chain_delay = CLICK_TICKS;
+        while chain_delay <= max_chain_delay && search.won.is_none() {
+            let mut chained_plan = plan.clone();
+            chained_plan.chain_delays.push(chain_delay);
+
+            // Once the gun has hit a wall there's nothing left to chain off, and waiting longer won't bring it back.
+            if !self.can_chain(state, &chained_plan)? {
+                break;
+            }
+
+            self.search_chain(state, chained_plan, num_chained_shots - 1, search)?;
+            chain_delay += chain_delay_step;
+        }
+
+        Ok(())
+    }
+
+    fn search_bullet_delay(&mut self, state: &SearchState, plan: GunPlan, search: &mut Search) -> Result<(), Error> {
+        let max_bullet_delay = self.seconds_to_ticks(self.config.solver_config.max_bullet_delay_seconds);
+        let num_volleys = self.level.gun_concepts[plan.gun_concept_index].bullets;
+
+        let mut bullet_delay = CLICK_TICKS;
+        while bullet_delay <= max_bullet_delay && search.won.is_none() {
+            let mut bullet_plan = plan.clone();
+            bullet_plan.bullet_delay = bullet_delay;
+
+            if !self.search_volleys(state, bullet_plan, num_volleys.saturating_sub(1), search)? {
+                break;
+            }
+
+            bullet_delay += 1;
+        }
+
+        Ok(())
+    }
+
+    // Plays the plan, then tries firing each volley it has left after it, spaced out by the chain delay step.
+    // Returns false when the plan falls apart.
+    fn search_volleys(&mut self, state: &SearchState, plan: GunPlan, num_volleys_left: usize, search: &mut Search) -> Result<bool, Error> {
+        if !self.evaluate(state, &plan, search)? {
+            return Ok(false);
+        }
+        if num_volleys_left == 0 {
+            return Ok(true);
+        }
+
+        let volley_delay_step = self.seconds_to_ticks(self.config.solver_config.chain_delay_step_seconds).max(1);
+        let max_volley_delay = self.seconds_to_ticks(self.config.solver_config.max_bullet_delay_seconds);
+
+        let mut volley_delay = CLICK_TICKS;
+        while volley_delay <= max_volley_delay && search.won.is_none() {
+            let mut volley_plan = plan.clone();
+            volley_plan.volley_delays.push(volley_delay);
+
+            // With no guns left to fire from, waiting longer won't help.
+            if !self.search_volleys(state, volley_plan, num_volleys_left - 1, search)? {
+                break;
+            }
+
+            volley_delay += volley_delay_step;
+        }
+
+        Ok(true)
+    }
+
+    // The level as `state` left it.
+    fn load_state(&mut self, state: &SearchState) -> Result<Simulation, Error> {
+        let mut simulation = self.load()?;
+        let args = self.tick_args();
+        for tick in &state.ticks {
+            simulation.update(&tick.key_states, &tick.mouse_states, &tick.mouse_pos, args);
+            simulation.sounds_to_play.clear();
+        }
+        Ok(simulation)
+    }
+
+    // Plays the plan up to its last chained gun shot and checks each one had a gun to shoot from.
+    fn can_chain(&mut self, state: &SearchState, plan: &GunPlan) -> Result<bool, Error> {
+        let plan_input = self.plan_input(plan, state.current_gun_concept_index, false);
+        let mut simulation = self.load_state(state)?;
+        Ok(self.run_plan_input(&mut simulation, &plan_input))
+    }
+
+    // Plays the plan on from `state`, and adds wherever it leaves the level to the search if that's somewhere new.
+    // Returns false when the plan falls apart, like a gun hitting a wall before it could fire.
+    fn evaluate(&mut self, state: &SearchState, plan: &GunPlan, search: &mut Search) -> Result<bool, Error> {
+        let plan_input = self.plan_input(plan, state.current_gun_concept_index, true);
+        let mut simulation = self.load_state(state)?;
+        if !self.run_plan_input(&mut simulation, &plan_input) {
+            return Ok(false);
+        }
+
+        let shots_taken = simulation.player.borrow().gun_concepts[plan.gun_concept_index].borrow().shots_taken();
+        if shots_taken < plan.chain_delays.len() + 1 {
+            return Ok(false);
+        }
+
+        let mut ticks = state.ticks.clone();
+        ticks.extend(plan_input.ticks);
+        self.settle(&mut simulation, &mut ticks);
+
+        let mut plans = state.plans.clone();
+        plans.push(plan.clone());
+        let next_state = SearchState {
+            plans,
+            ticks,
+            shots_taken: state.shots_taken + shots_taken,
+            current_gun_concept_index: simulation.player.borrow().current_gun_concept_index,
+            gun_concept_indices: usable_gun_concepts(&simulation),
+        };
+
+        if simulation.game_ended_state.won {
+            search.won = Some(next_state);
+        } else if !simulation.game_ended_state.game_ended && search.seen.insert(state_key(&simulation)) {
+            search.states.push(next_state);
+        }
+
+        Ok(true)
+    }
+
+    fn run_plan_input(&self, simulation: &mut Simulation, plan_input: &PlanInput) -> bool {
+        let args = self.tick_args();
+        for (tick_index, tick) in plan_input.ticks.iter().enumerate() {
+            if plan_input.needs_guns.contains(&tick_index) && !has_guns_in_play(simulation) {
+                return false;
+            }
+            simulation.update(&tick.key_states, &tick.mouse_states, &tick.mouse_pos, args);
+            simulation.sounds_to_play.clear();
+        }
+
+        true
+    }
+
+    // Steps with nothing pressed until no bullets or gun axes are flying, then once more for the game to notice if that
+    // won or lost it. The idle ticks are added to `ticks`.
+    fn settle(&self, simulation: &mut Simulation, ticks: &mut Vec<ReplayTick>) {
+        let args = self.tick_args();
+        let idle_tick = idle_tick(ticks);
+        let max_settle_ticks = self.seconds_to_ticks(self.config.solver_config.settle_seconds);
+
+        let mut settle_ticks = 0;
+        while settle_ticks < max_settle_ticks && has_projectiles_in_flight(simulation) && !simulation.game_ended_state.game_ended {
+            simulation.update(&idle_tick.key_states, &idle_tick.mouse_states, &idle_tick.mouse_pos, args);
+            simulation.sounds_to_play.clear();
+            ticks.push(idle_tick.clone());
+            settle_ticks += 1;
+        }
+
+        simulation.update(&idle_tick.key_states, &idle_tick.mouse_states, &idle_tick.mouse_pos, args);
+        simulation.sounds_to_play.clear();
+        ticks.push(idle_tick);
+    }
+
+    // Turns a plan into per-tick input, starting with the player holding the gun concept at `current_gun_concept_index`.
+    fn plan_input(&self, plan: &GunPlan, current_gun_concept_index: usize, include_bullet: bool) -> PlanInput {
+        let mouse_pos = self.player_position
+            + Vector2 {
+                x: plan.aim_angle.cos() * AIM_DISTANCE,
+                y: plan.aim_angle.sin() * AIM_DISTANCE,
+            };
+        let mut plan_input = PlanInput {
+            ticks: Vec::new(),
+            needs_guns: Vec::new(),
+        };
+
+        let num_gun_concepts = self.num_gun_concepts;
+        let num_switches = (plan.gun_concept_index + num_gun_concepts - current_gun_concept_index) % num_gun_concepts;
+        for _ in 0..num_switches {
+            push_key_press(&mut plan_input.ticks, Key::Q, mouse_pos);
+        }
+
+        push_click(&mut plan_input.ticks, MouseButton::Left, mouse_pos, CLICK_TICKS);
+        for &chain_delay in &plan.chain_delays {
+            pad_to(&mut plan_input.ticks, mouse_pos, chain_delay - CLICK_TICKS);
+            plan_input.needs_guns.push(plan_input.ticks.len());
+            push_click(&mut plan_input.ticks, MouseButton::Left, mouse_pos, CLICK_TICKS);
+        }
+
+        if include_bullet {
+            pad_to(&mut plan_input.ticks, mouse_pos, plan.bullet_delay - CLICK_TICKS);
+            plan_input.needs_guns.push(plan_input.ticks.len());
+            push_click(&mut plan_input.ticks, MouseButton::Right, mouse_pos, BULLET_CLICK_TICKS);
+            for &volley_delay in &plan.volley_delays {
+                pad_to(&mut plan_input.ticks, mouse_pos, volley_delay - BULLET_CLICK_TICKS);
+                plan_input.needs_guns.push(plan_input.ticks.len());
+                push_click(&mut plan_input.ticks, MouseButton::Right, mouse_pos, BULLET_CLICK_TICKS);
+            }
+        }
+
+        plan_input
+    }
+
+    fn solution(&self, won: SearchState) -> Solution {
+        let mut config = self.config.clone();
+        config.game_loop_config.ticks_per_second = config.solver_config.ticks_per_second;
+
+        Solution {
+            plans: won.plans,
+            shots_taken: won.shots_taken,
+            replay: Replay {
+                version: REPLAY_VERSION,
+                level_name: self.level_name.clone(),
+                config,
+                ticks: won.ticks,
+            },
+        }
+    }
+}
+
+// Where the enemies are and how hurt, which walls are left and how worn down, which doors are open and what's left to
+// shoot with. Positions are rounded to the cell so enemies on the move don't make every state look new.
+fn state_key(simulation: &Simulation) -> StateKey {
+    let cell_width = f64::from(simulation.tuning.cell_width());
+    let cell_height = f64::from(simulation.tuning.cell_height());
+    let cell = |position: &Vector2| vec![(position.x / cell_width).floor() as i64, (position.y / cell_height).floor() as i64];
+
+    let mut key = Vec::new();
+    for enemy in &simulation.enemies {
+        let enemy = enemy.borrow();
+        key.extend(cell(&enemy.position));
+        key.push(i64::from(enemy.health));
+        key.push(enemy.is_armored as i64);
+    }
+    // Keeps a missing enemy from reading as a missing wall.
+    key.push(-1);
+    for wall in &simulation.walls {
+        let wall = wall.borrow();
+        key.extend(cell(&wall.position));
+        key.push(i64::from(wall.health));
+    }
+    key.push(-1);
+    key.extend(simulation.doors.iter().map(|door| door.borrow().is_open as i64));
+    for gun_concept in &simulation.player.borrow().gun_concepts {
+        let gun_concept = gun_concept.borrow();
+        key.push(gun_concept.can_shoot_gun() as i64);
+        key.push(gun_concept.bullets_shot() as i64);
+    }
+    key
+}
+
+fn usable_gun_concepts(simulation: &Simulation) -> Vec<usize> {
+    let player = simulation.player.borrow();
+    (0..player.gun_concepts.len()).filter(|&gun_concept_index| player.gun_concepts[gun_concept_index].borrow().can_shoot_gun()).collect()
+}
+
+fn has_guns_in_play(simulation: &Simulation) -> bool {
+    let player = simulation.player.borrow();
+    let gun_concept = player.gun_concepts[player.current_gun_concept_index].borrow();
+    gun_concept.guns().iter().any(|gun| !gun.borrow().get_should_delete())
+}
+
+fn has_projectiles_in_flight(simulation: &Simulation) -> bool {
+    simulation.renderables.iter().flat_map(|renderable_layer| renderable_layer.iter()).any(|renderable| {
+        let object_type = renderable.borrow().get_object_type();
+        object_type == ObjectType::Bullet || object_type == ObjectType::LethalGun
+    })
+}
+
+fn idle_tick(ticks: &[ReplayTick]) -> ReplayTick {
+    ReplayTick {
+        mouse_pos: ticks.last().map_or_else(Vector2::default, |tick| tick.mouse_pos),
+        ..ReplayTick::default()
+    }
+}
+
+fn pad_to(ticks: &mut Vec<ReplayTick>, mouse_pos: Vector2, num_ticks: u32) {
+    for _ in 0..num_ticks {
+        ticks.push(ReplayTick {
+            mouse_pos,
+            ..ReplayTick::default()
+        });
+    }
+}
+
+fn button_state(held: bool, pressed: bool, released: bool) -> ButtonState {
+    ButtonState {
+        held,
+        pressed,
+        released,
+    }
+}
+
+// Pressed, then held for the rest of `num_ticks`, released on the last one.
+fn push_click(ticks: &mut Vec<ReplayTick>, button: MouseButton, mouse_pos: Vector2, num_ticks: u32) {
+    for tick_index in 0..num_ticks {
+        let state = if tick_index == 0 {
+            button_state(false, true, false)
+        } else if tick_index + 1 == num_ticks {
+            button_state(false, false, true)
+        } else {
+            button_state(true, false, false)
+        };
+
+        let mut tick = ReplayTick {
+            mouse_pos,
+            ..ReplayTick::default()
+        };
+        tick.mouse_states.insert(button, state);
+        ticks.push(tick);
+    }
+}
+
+fn push_key_press(ticks: &mut Vec<ReplayTick>, key: Key, mouse_pos: Vector2) {
+    for &state in &[button_state(false, true, false), button_state(false, false, true)] {
+        let mut tick = ReplayTick {
+            mouse_pos,
+            ..ReplayTick::default()
+        };
+        tick.key_states.insert(key, state);
+        ticks.push(tick);
+    }
+}
+
+pub fn describe_plan(plan: &GunPlan, ticks_per_second: u32) -> String {
+    let seconds = |ticks: u32| f64::from(ticks) / f64::from(ticks_per_second);
+    let mut description = format!("gun concept {}: aim at {:.0} degrees, shoot a gun", plan.gun_concept_index + 1, plan.aim_angle.to_degrees());
+    for &chain_delay in &plan.chain_delays {
+        description += &format!(", {:.2}s later shoot another", seconds(chain_delay));
+    }
+    description += &format!(", {:.2}s later fire the bullets", seconds(plan.bullet_delay));
+    for &volley_delay in &plan.volley_delays {
+        description += &format!(", {:.2}s later fire them again", seconds(volley_delay));
+    }
+    description
+}
+
+// Prints the cheapest solution found and saves it as a replay. Returns false when the level looks unwinnable.
+pub fn run_solver(level_name: &str, config: Config) -> Result<bool, Error> {
+    let replay_directory = Path::new(&config.replay_config.replay_directory).to_path_buf();
+    let ticks_per_second = config.solver_config.ticks_per_second;
+    let mut solver = Solver::new(level_name, config)?;
+
+    match solver.solve()? {
+        Some(solution) => {
+            println!("Solved {} in {} gun shots, the fewest possible within the search steps:", level_name, solution.shots_taken);
+            for plan in &solution.plans {
+                println!("  {}", describe_plan(plan, ticks_per_second));
+            }
+            if let Some(par) = solver.level.par {
+                println!("The level's par is {} gun shots.", par);
+            }
+            let path = replay::write_replay_to_file(&solution.replay, &replay_directory)?;
+            println!("Saved the solution as a replay to {}", path.display());
+            Ok(true)
+        }
+        None => {
+            println!("Found no way to beat {} within the search steps in solver_config.", level_name);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    // A 32 by 18 room with the player on the left, and `middle` written into the player's row.
+    fn room(middle: &str) -> LevelDocument {
+        let wall_row = "W".repeat(32);
+        let ground_row = format!("W{}W", "_".repeat(30));
+        let player_row = format!("W__P{}{}W", middle, "_".repeat(27 - middle.len()));
+        let mut tiles = vec![wall_row.clone()];
+        for row in 1..17 {
+            tiles.push(if row == 8 { player_row.clone() } else { ground_row.clone() });
+        }
+        tiles.push(wall_row);
+
+        let json = serde_json::json!({
+            "title": "solver test",
+            "gun_concepts": [{ "kind": "hand_gun" }],
+            "tiles": tiles,
+        });
+        serde_json::from_value(json).unwrap()
+    }
+
+    // Coarse steps keep the search down to a handful of plays.
+    fn coarse_config() -> Config {
+        let mut config = config::read_default_config().unwrap();
+        config.solver_config.angle_step_degrees = 90.0;
+        config.solver_config.max_gun_shots = 1;
+        config.solver_config.max_bullet_delay_seconds = 0.5;
+        config.solver_config.settle_seconds = 3.0;
+        config
+    }
+
+    // Guns that fly straight, so bullets go where they're aimed, and breakable walls that come down in one hit.
+    fn walled_in_config() -> Config {
+        let mut config = coarse_config();
+        for gun_definition in config.gun_definitions.values_mut() {
+            gun_definition.spin_rate = 0.0;
+        }
+        config.wall_config.breakable_health = 1;
+        config
+    }
+
+    fn solver_for(level: LevelDocument, config: Config) -> Result<Solver, Error> {
+        let vfs = Rc::new(Vfs::from_config(&config)?);
+        Solver::with_level("solver test", level, config, vfs)
+    }
+
+    // Walls in columns `first_column` to `last_column` of the player's row and the rows either side, leaving the inside
+    // of the player's row open. Enemies and breakable walls are left where they are.
+    fn box_in(level: &mut LevelDocument, first_column: usize, last_column: usize) {
+        for row in 7..10 {
+            let mut tiles: Vec<char> = level.tiles[row].chars().collect();
+            for (column, tile) in tiles.iter_mut().enumerate().take(last_column + 1).skip(first_column) {
+                if (row != 8 || column == first_column || column == last_column) && !"EB".contains(*tile) {
+                    *tile = 'W';
+                }
+            }
+            level.tiles[row] = tiles.into_iter().collect();
+        }
+    }
+
+    #[test]
+    fn plan_input_lays_out_clicks_and_delays() {
+        let solver = solver_for(room("________E"), coarse_config()).unwrap();
+        let plan = GunPlan {
+            gun_concept_index: 0,
+            aim_angle: 0.0,
+            chain_delays: vec![5],
+            bullet_delay: 4,
+            volley_delays: vec![6],
+        };
+
+        let plan_input = solver.plan_input(&plan, 0, true);
+        // A gun click, a chain delay, a gun click, the bullet delay, a two tick bullet click and another volley after it.
+        assert_eq!(plan_input.ticks.len(), 5 + 4 + 6 + 2);
+        assert_eq!(plan_input.needs_guns, vec![5, 9, 15]);
+        assert!(plan_input.ticks[0].mouse_states[&MouseButton::Left].pressed);
+        assert!(plan_input.ticks[5].mouse_states[&MouseButton::Left].pressed);
+        assert!(plan_input.ticks[9].mouse_states[&MouseButton::Right].pressed);
+        assert!(plan_input.ticks[10].mouse_states[&MouseButton::Right].released);
+        assert!(plan_input.ticks[15].mouse_states[&MouseButton::Right].pressed);
+        assert!(plan_input.ticks.iter().all(|tick| tick.mouse_pos.x > solver.player_position.x));
+
+        let plan_input = solver.plan_input(&plan, 0, false);
+        assert_eq!(plan_input.ticks.len(), 5 + 3);
+        assert_eq!(plan_input.needs_guns, vec![5]);
+    }
+
+    #[test]
+    fn solves_an_enemy_in_the_line_of_fire() {
+        let mut solver = solver_for(room("________E"), coarse_config()).unwrap();
+
+        let solution = solver.solve().unwrap().expect("the enemy is straight to the right of the player");
+        assert_eq!(solution.shots_taken, 1);
+        assert!(!solution.replay.ticks.is_empty());
+    }
+
+    #[test]
+    fn gives_up_on_a_walled_off_enemy() {
+        let mut level = room("________E");
+        box_in(&mut level, 11, 13);
+
+        let mut solver = solver_for(level, coarse_config()).unwrap();
+        assert!(solver.solve().unwrap().is_none());
+    }
+
+    // The first bullet only breaks the wall in front of the enemy, so neither plan wins alone, but played one after the
+    // other they do.
+    #[test]
+    fn solves_a_level_only_plans_played_together_can_win() {
+        let mut level = room("________BE");
+        level.gun_concepts = serde_json::from_value(serde_json::json!([{ "kind": "hand_gun", "gun_depth": 1 }, { "kind": "hand_gun", "gun_depth": 1 }])).unwrap();
+        box_in(&mut level, 12, 14);
+        let mut config = walled_in_config();
+
+        config.solver_config.max_gun_shots = 1;
+        let mut solver = solver_for(level.clone(), config.clone()).unwrap();
+        assert!(solver.solve().unwrap().is_none());
+
+        config.solver_config.max_gun_shots = 2;
+        let mut solver = solver_for(level, config).unwrap();
+        let solution = solver.solve().unwrap().expect("one bullet for the wall and one for the enemy");
+        assert_eq!(solution.shots_taken, 2);
+        assert_eq!(solution.plans.len(), 2);
+
+        // The replay plays both plans on one copy of the level and wins it.
+        let mut simulation = solver.load().unwrap();
+        for tick in &solution.replay.ticks {
+            simulation.update(&tick.key_states, &tick.mouse_states, &tick.mouse_pos, solver.tick_args());
+        }
+        assert!(simulation.game_ended_state.won);
+    }
+
+    // Same again with one gun concept that fires twice.
+    #[test]
+    fn solves_a_level_that_needs_a_second_volley() {
+        let mut level = room("________BE");
+        level.gun_concepts = serde_json::from_value(serde_json::json!([{ "kind": "hand_gun", "gun_depth": 1, "bullets": 2 }])).unwrap();
+        box_in(&mut level, 12, 14);
+        let config = walled_in_config();
+
+        let mut solver = solver_for(level, config).unwrap();
+        let solution = solver.solve().unwrap().expect("the first volley breaks the wall and the second gets the enemy");
+        assert_eq!(solution.shots_taken, 1);
+        assert_eq!(solution.plans[0].volley_delays.len(), 1);
+    }
+
+    #[test]
+    fn rejects_search_steps_that_never_end() {
+        let mut config = coarse_config();
+        config.solver_config.angle_step_degrees = 0.0;
+        config.solver_config.max_gun_shots = 0;
+
+        match solver_for(room("________E"), config) {
+            Err(Error::MalformedConfig {
+                message,
+                ..
+            }) => {
+                assert!(message.contains("angle_step_degrees"));
+                assert!(message.contains("max_gun_shots"));
+            }
+            _ => panic!("expected a malformed config"),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::asset_loader::AssetLoader;
+use crate::error::Error;
 use ears::*;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -7,12 +8,25 @@ use std::rc::Rc;
 
 pub struct SoundManager {
     pub asset_loader: Rc<AssetLoader>,
-    pub sounds_by_filename: HashMap<&'static str, Rc<RefCell<Sound>>>,
+    pub sounds_by_filename: HashMap<String, Rc<RefCell<Sound>>>,
 }
 
 impl SoundManager {
-    pub fn get(&mut self, sound_name: &'static str) -> Rc<RefCell<Sound>> {
-        let asset_loader = &self.asset_loader;
-        self.sounds_by_filename.entry(sound_name).or_insert_with(|| Rc::new(RefCell::new(asset_loader.deref().load_sound(sound_name)))).clone()
+    pub fn get(&mut self, sound_name: &str) -> Result<Rc<RefCell<Sound>>, Error> {
+        if let Some(sound) = self.sounds_by_filename.get(sound_name) {
+            return Ok(sound.clone());
+        }
+
+        let sound = Rc::new(RefCell::new(self.asset_loader.deref().load_sound(sound_name)?));
+        self.sounds_by_filename.insert(sound_name.to_string(), sound.clone());
+        Ok(sound)
+    }
+
+    // A sound that won't load isn't worth stopping the game over.
+    pub fn play(&mut self, sound_name: &str) {
+        match self.get(sound_name) {
+            Ok(sound) => sound.borrow_mut().play(),
+            Err(err) => println!("{}", err),
+        }
     }
 }
@@ -0,0 +1,92 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::bullet::Bullet;
+use crate::bullet_behavior::BulletBehavior;
+use crate::collidable::Collision;
+use crate::collidable_object::CollidableObject;
+use crate::config::CollisionConfig;
+use crate::config::CollisionResponse;
+use crate::normal_bullet_behavior::NormalBulletBehavior;
+use crate::object_type::ObjectType;
+use crate::renderable_object::RenderableObject;
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
+
+pub struct SplitBulletBehavior {
+    pub count: usize,
+    pub spread_degrees: f64,
+}
+
+impl BulletBehavior for SplitBulletBehavior {
+    fn name(&self) -> &str {
+        "split"
+    }
+
+    fn collision_response(&self, _bullet: &Bullet, other_object_type: ObjectType, collision_config: &CollisionConfig) -> CollisionResponse {
+        if other_object_type.is_wall() {
+            CollisionResponse::Destroy
+        } else {
+            collision_config.response(ObjectType::Bullet, other_object_type)
+        }
+    }
+
+    // The pieces fan out around the way the bullet would have bounced, starting clear of the wall so they don't hit it
+    // straight away.
+    fn collide(&self, bullet: &mut Bullet, collision: &Collision) -> Vec<WorldReq> {
+        if !collision.other_object_type.is_wall() || collision.response != CollisionResponse::Destroy {
+            return Vec::new();
+        }
+
+        let speed_into_surface = bullet.velocity.dot(&collision.normal);
+        let bounced_velocity = bullet.velocity - collision.normal * (2.0 * speed_into_surface);
+        let bounced_rotation = bounced_velocity.y.atan2(bounced_velocity.x);
+        let speed = bounced_velocity.magnitude();
+        let clearance = bullet.collidable_object.width.max(bullet.collidable_object.height) * bullet.scale;
+        let position = collision.point + collision.normal * clearance;
+
+        let mut world_reqs = Vec::new();
+        for piece_index in 0..self.count {
+            let rotation = bounced_rotation + self.spread_offset(piece_index);
+            let piece = Bullet {
+                position,
+                rotation,
+                previous_position: position,
+                previous_rotation: rotation,
+                scale: bullet.scale,
+                renderable_object: RenderableObject {
+                    texture: bullet.renderable_object.texture.clone(),
+                },
+                velocity: Vector2 {
+                    x: rotation.cos(),
+                    y: rotation.sin(),
+                } * speed,
+                should_delete: false,
+                collidable_object: CollidableObject {
+                    width: bullet.collidable_object.width,
+                    height: bullet.collidable_object.height,
+                    shape: bullet.collidable_object.shape.clone(),
+                },
+                is_visible: true,
+                object_type: bullet.object_type,
+                bullet_behavior: Rc::new(NormalBulletBehavior),
+                bounces: 0,
+                pierced: Vec::new(),
+            };
+            world_reqs.append(&mut Bullet::world_requests(Rc::new(RefCell::new(piece))));
+        }
+        world_reqs
+    }
+}
+
+impl SplitBulletBehavior {
+    // Pieces fan out from one edge of the spread to the other, like guns do.
+    fn spread_offset(&self, piece_index: usize) -> f64 {
+        if self.count < 2 {
+            return 0.0;
+        }
+
+        let spread = self.spread_degrees.to_radians();
+        spread * 0.5 - spread * piece_index as f64 / (self.count - 1) as f64
+    }
+}
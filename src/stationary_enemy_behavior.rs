@@ -0,0 +1,19 @@
+use crate::enemy_behavior::EnemyBehavior;
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
+
+pub struct StationaryEnemyBehavior;
+
+impl EnemyBehavior for StationaryEnemyBehavior {
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    fn velocity(&mut self, _position: &Vector2, _player_position: &Vector2, _dt: f64) -> Vector2 {
+        Vector2::default()
+    }
+
+    fn act(&mut self, _position: &Vector2, _player_position: &Vector2, _dt: f64) -> Vec<WorldReq> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,98 @@
+use crate::collidable::Collidable;
+use crate::collidable::Collision;
+use crate::collidable_object::CollidableObject;
+use crate::door::Door;
+use crate::game_object::GameObject;
+use crate::object_type::ObjectType;
+use crate::renderable::Renderable;
+use crate::renderable_object::RenderableObject;
+use crate::simulation::WorldReq;
+use crate::vector2::Vector2;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Opens its doors if they're closed and closes them if they're open, every time it's hit.
+pub struct Switch {
+    pub position: Vector2,
+    pub rotation: f64,
+    pub scale: f64,
+    pub renderable_object: RenderableObject,
+    pub on_renderable_object: RenderableObject,
+    pub collidable_object: CollidableObject,
+    pub is_visible: bool,
+    pub is_on: bool,
+    pub doors: Vec<Rc<RefCell<Door>>>,
+}
+
+impl GameObject for Switch {
+    fn get_position(&self) -> &Vector2 {
+        &self.position
+    }
+
+    fn get_rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    fn get_scale(&self) -> f64 {
+        self.scale
+    }
+
+    fn get_should_delete(&self) -> bool {
+        false
+    }
+
+    #[allow(unused_variables)]
+    fn set_should_delete(&mut self, should_delete: bool) {
+        // do nothing
+    }
+
+    fn get_object_type(&self) -> ObjectType {
+        ObjectType::Switch
+    }
+}
+
+impl Renderable for Switch {
+    fn get_renderable_object(&self) -> &RenderableObject {
+        if self.is_on {
+            &self.on_renderable_object
+        } else {
+            &self.renderable_object
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+}
+
+impl Collidable for Switch {
+    fn get_collidable_object(&self) -> &CollidableObject {
+        &self.collidable_object
+    }
+
+    fn get_velocity(&self) -> Vector2 {
+        Vector2::default()
+    }
+
+    fn set_velocity(&mut self, _velocity: Vector2) {}
+
+    fn set_position(&mut self, _position: Vector2) {}
+
+    fn collide(&mut self, _collision: &Collision) -> Vec<WorldReq> {
+        Vec::new()
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    // Whatever the table says flips the switch, a bullet, a gun or a blast, does so without breaking it.
+    fn survives_hit(&mut self, _direction_to_hitter: Vector2, _hitter_kind_names: &[String]) -> bool {
+        self.is_on = !self.is_on;
+        for door in &self.doors {
+            let mut door = door.borrow_mut();
+            door.is_open = !door.is_open;
+        }
+        true
+    }
+}
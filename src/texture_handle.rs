@@ -0,0 +1,16 @@
+use piston_window::ImageSize;
+
+// A texture as the simulation sees it: just a name and a size. The renderer
+// looks the actual GPU texture up by name.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TextureHandle {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageSize for TextureHandle {
+    fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
@@ -1,4 +1,7 @@
 use crate::asset_loader::AssetLoader;
+use crate::error::Error;
+use crate::texture_handle::TextureHandle;
+use crate::texture_registry::TextureRegistry;
 use piston_window::*;
 use std::collections::HashMap;
 use std::ops::Deref;
@@ -6,12 +9,28 @@ use std::rc::Rc;
 
 pub struct TextureManager {
     pub asset_loader: Rc<AssetLoader>,
-    pub textures_by_filename: HashMap<&'static str, Rc<G2dTexture>>,
+    pub textures_by_filename: HashMap<String, Rc<G2dTexture>>,
 }
 
 impl TextureManager {
-    pub fn get(&mut self, texture_name: &'static str) -> Rc<G2dTexture> {
-        let asset_loader = &self.asset_loader;
-        self.textures_by_filename.entry(texture_name).or_insert_with(|| Rc::new(asset_loader.deref().load_texture(texture_name))).clone()
+    pub fn get(&mut self, texture_name: &str) -> Result<Rc<G2dTexture>, Error> {
+        if let Some(texture) = self.textures_by_filename.get(texture_name) {
+            return Ok(texture.clone());
+        }
+
+        let texture = Rc::new(self.asset_loader.deref().load_texture(texture_name)?);
+        self.textures_by_filename.insert(texture_name.to_string(), texture.clone());
+        Ok(texture)
+    }
+}
+
+impl TextureRegistry for TextureManager {
+    fn get_handle(&mut self, texture_name: &str) -> Result<TextureHandle, Error> {
+        let (width, height) = self.get(texture_name)?.get_size();
+        Ok(TextureHandle {
+            name: texture_name.to_string(),
+            width,
+            height,
+        })
     }
 }
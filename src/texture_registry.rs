@@ -0,0 +1,6 @@
+use crate::error::Error;
+use crate::texture_handle::TextureHandle;
+
+pub trait TextureRegistry {
+    fn get_handle(&mut self, texture_name: &str) -> Result<TextureHandle, Error>;
+}
@@ -0,0 +1,78 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use piston_window::ImageSize;
+
+use crate::bullet::Bullet;
+use crate::collidable_object::CollidableObject;
+use crate::config::ColliderShape;
+use crate::enemy_behavior::EnemyBehavior;
+use crate::normal_bullet_behavior::NormalBulletBehavior;
+use crate::object_type::ObjectType;
+use crate::renderable_object::RenderableObject;
+use crate::simulation::WorldReq;
+use crate::texture_handle::TextureHandle;
+use crate::vector2::Vector2;
+
+pub struct TurretEnemyBehavior {
+    // Seconds between shots.
+    pub fire_interval: f64,
+    pub time_until_fire: f64,
+    pub bullet_texture: TextureHandle,
+    pub bullet_collider: ColliderShape,
+    pub bullet_scale: f64,
+    pub bullet_speed: f64,
+    pub bullet_sound: String,
+}
+
+impl EnemyBehavior for TurretEnemyBehavior {
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    fn velocity(&mut self, _position: &Vector2, _player_position: &Vector2, _dt: f64) -> Vector2 {
+        Vector2::default()
+    }
+
+    fn act(&mut self, position: &Vector2, player_position: &Vector2, dt: f64) -> Vec<WorldReq> {
+        self.time_until_fire -= dt;
+        if self.time_until_fire > 0.0 {
+            return Vec::new();
+        }
+        self.time_until_fire += self.fire_interval;
+
+        let mut world_reqs = Bullet::world_requests(Rc::new(RefCell::new(self.make_bullet(position, player_position))));
+        world_reqs.push(WorldReq::play_sound(&self.bullet_sound));
+        world_reqs
+    }
+}
+
+impl TurretEnemyBehavior {
+    fn make_bullet(&self, position: &Vector2, player_position: &Vector2) -> Bullet {
+        let to_player = *player_position - *position;
+        let rotation = to_player.y.atan2(to_player.x);
+
+        Bullet {
+            position: *position,
+            rotation,
+            previous_position: *position,
+            previous_rotation: rotation,
+            scale: self.bullet_scale,
+            renderable_object: RenderableObject {
+                texture: self.bullet_texture.clone(),
+            },
+            velocity: to_player.normalized() * self.bullet_speed,
+            should_delete: false,
+            collidable_object: CollidableObject {
+                width: f64::from(self.bullet_texture.get_size().0),
+                height: f64::from(self.bullet_texture.get_size().1),
+                shape: self.bullet_collider.clone(),
+            },
+            is_visible: true,
+            object_type: ObjectType::EnemyBullet,
+            bullet_behavior: Rc::new(NormalBulletBehavior),
+            bounces: 0,
+            pierced: Vec::new(),
+        }
+    }
+}
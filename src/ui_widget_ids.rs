@@ -13,9 +13,13 @@ widget_ids! {
         shots_taken_hud[],
         bullets_remaining_hud[],
         bullets_hud[],
+        bullet_kinds_hud[],
+        enemies_remaining_hud,
+        enemy_health_hud[],
 
         // Main Menu World List
         world_list[],
+        world_list_headers[],
 
         // FPS Counter
         fps_text,
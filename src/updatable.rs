@@ -1,7 +1,7 @@
 use crate::game_object::GameObject;
 use crate::input;
 use crate::vector2::Vector2;
-use crate::world::WorldReq; // circular dependency?
+use crate::simulation::WorldReq; // circular dependency?
 use piston_window::Key;
 use piston_window::MouseButton;
 use piston_window::UpdateArgs;
@@ -1,7 +1,8 @@
+use crate::serde::{Deserialize, Serialize};
 use std::fmt;
 use std::ops::*;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Vector2 {
     pub x: f64,
     pub y: f64,
@@ -26,9 +27,9 @@ impl Vector2 {
         }
     }
 
-    // pub fn dot(&self, rhs: &Vector2) -> f64 {
-    //     return (self.x * rhs.x) + (self.y * rhs.y);
-    // }
+    pub fn dot(&self, rhs: &Vector2) -> f64 {
+        (self.x * rhs.x) + (self.y * rhs.y)
+    }
 }
 
 impl fmt::Display for Vector2 {
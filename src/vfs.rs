@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::error::Error;
+
+const ARCHIVE_MAGIC: &[u8; 8] = b"SHOOTPAK";
+// The magic and the index length.
+const ARCHIVE_HEADER_LENGTH: u64 = 16;
+
+// Asset paths always use forward slashes and are relative to a mount point, e.g. `textures/bullet.png`.
+// Backslashes are accepted too, so old Windows-style paths keep working everywhere.
+pub fn normalize_asset_path(asset_path: &str) -> String {
+    asset_path.replace('\\', "/").split('/').filter(|part| !part.is_empty() && *part != ".").collect::<Vec<_>>().join("/")
+}
+
+// A single file holding many assets: the magic, the index length as a little-endian u64, a JSON index
+// of asset path to (offset, length) within the data that follows, then the data.
+pub struct PackedArchive {
+    pub path: PathBuf,
+    pub entries: BTreeMap<String, (u64, u64)>,
+    pub data_offset: u64,
+}
+
+impl PackedArchive {
+    pub fn open(path: &Path) -> io::Result<PackedArchive> {
+        let mut file = File::open(path)?;
+
+        let mut magic = [0u8; 8];
+        file.read_exact(&mut magic)?;
+        if &magic != ARCHIVE_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} isn't a packed asset archive", path.display())));
+        }
+
+        let mut index_length = [0u8; 8];
+        file.read_exact(&mut index_length)?;
+        let index_length = u64::from_le_bytes(index_length);
+        // The header is untrusted, so don't let a corrupt one ask for more memory than the file could hold.
+        let file_length = file.metadata()?.len();
+        if index_length > file_length.saturating_sub(ARCHIVE_HEADER_LENGTH) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} has an index longer than the file", path.display())));
+        }
+
+        let mut index = vec![0u8; index_length as usize];
+        file.read_exact(&mut index)?;
+        let entries = serde_json::from_slice(&index).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(PackedArchive {
+            path: path.to_path_buf(),
+            entries,
+            data_offset: ARCHIVE_HEADER_LENGTH + index_length,
+        })
+    }
+
+    pub fn read(&self, asset_path: &str) -> Option<io::Result<Vec<u8>>> {
+        let &(offset, length) = self.entries.get(asset_path)?;
+        Some(File::open(&self.path).and_then(|mut file| {
+            let data_length = file.metadata()?.len().saturating_sub(self.data_offset);
+            match offset.checked_add(length) {
+                Some(end) if end <= data_length => {}
+                _ => return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} runs past the end of {}", asset_path, self.path.display()))),
+            }
+            file.seek(SeekFrom::Start(self.data_offset + offset))?;
+            let mut bytes = vec![0u8; length as usize];
+            file.read_exact(&mut bytes)?;
+            Ok(bytes)
+        }))
+    }
+}
+
+// Packs every file under `directory` into an archive at `archive_path`.
+pub fn write_packed_archive(directory: &Path, archive_path: &Path) -> io::Result<usize> {
+    let mut asset_paths = Vec::new();
+    collect_files(directory, directory, &mut asset_paths)?;
+    asset_paths.sort();
+
+    let mut entries: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    let mut data = Vec::new();
+    for asset_path in &asset_paths {
+        let bytes = fs::read(directory.join(asset_path))?;
+        entries.insert(asset_path.clone(), (data.len() as u64, bytes.len() as u64));
+        data.extend_from_slice(&bytes);
+    }
+
+    let index = serde_json::to_vec(&entries).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let mut file = File::create(archive_path)?;
+    file.write_all(ARCHIVE_MAGIC)?;
+    file.write_all(&(index.len() as u64).to_le_bytes())?;
+    file.write_all(&index)?;
+    file.write_all(&data)?;
+
+    Ok(asset_paths.len())
+}
+
+fn collect_files(root: &Path, directory: &Path, asset_paths: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, asset_paths)?;
+        } else if let Ok(relative_path) = path.strip_prefix(root) {
+            asset_paths.push(normalize_asset_path(&relative_path.to_string_lossy()));
+        }
+    }
+    Ok(())
+}
+
+pub enum Mount {
+    Directory(PathBuf),
+    Archive(PackedArchive),
+}
+
+// Every place assets can come from. Later mounts win, so mods can replace base assets file by file.
+pub struct Vfs {
+    pub mounts: Vec<Mount>,
+}
+
+impl Vfs {
+    // Mounts each of `asset_config.mount_points` that exists: directories as they are, files as packed archives.
+    // Relative directories that aren't under the working directory are looked for near it, like `assets` always was.
+    pub fn from_config(config: &Config) -> Result<Vfs, Error> {
+        let mut mounts = Vec::new();
+        for mount_point in &config.asset_config.mount_points {
+            let path = Path::new(mount_point);
+            if path.is_file() {
+                let archive = PackedArchive::open(path).map_err(|err| Error::bad_asset(&path.display().to_string(), &err))?;
+                mounts.push(Mount::Archive(archive));
+            } else if path.is_dir() {
+                mounts.push(Mount::Directory(path.to_path_buf()));
+            } else if let Ok(found_path) = find_folder::Search::ParentsThenKids(3, 3).for_folder(mount_point) {
+                mounts.push(Mount::Directory(found_path));
+            }
+        }
+
+        Ok(Vfs {
+            mounts,
+        })
+    }
+
+    pub fn exists(&self, asset_path: &str) -> bool {
+        let asset_path = normalize_asset_path(asset_path);
+        self.mounts.iter().any(|mount| match mount {
+            Mount::Directory(directory) => directory.join(&asset_path).is_file(),
+            Mount::Archive(archive) => archive.entries.contains_key(&asset_path),
+        })
+    }
+
+    pub fn read(&self, asset_path: &str) -> io::Result<Vec<u8>> {
+        let normalized_path = normalize_asset_path(asset_path);
+        for mount in self.mounts.iter().rev() {
+            match mount {
+                Mount::Directory(directory) => {
+                    let path = directory.join(&normalized_path);
+                    if path.is_file() {
+                        return fs::read(path);
+                    }
+                }
+                Mount::Archive(archive) => {
+                    if let Some(bytes) = archive.read(&normalized_path) {
+                        return bytes;
+                    }
+                }
+            }
+        }
+
+        Err(io::Error::new(io::ErrorKind::NotFound, format!("no mounted asset at {}", normalized_path)))
+    }
+
+    // The file on disk an asset is read from: the asset itself in a directory mount, or the archive holding it.
+    pub fn source_path(&self, asset_path: &str) -> Option<PathBuf> {
+        let normalized_path = normalize_asset_path(asset_path);
+        self.mounts.iter().rev().find_map(|mount| match mount {
+            Mount::Directory(directory) => Some(directory.join(&normalized_path)).filter(|path| path.is_file()),
+            Mount::Archive(archive) => Some(archive.path.clone()).filter(|_| archive.entries.contains_key(&normalized_path)),
+        })
+    }
+
+    // For libraries that only open files by path. Assets inside archives get copied out to a temp folder first.
+    pub fn file_path(&self, asset_path: &str) -> io::Result<PathBuf> {
+        let normalized_path = normalize_asset_path(asset_path);
+        for mount in self.mounts.iter().rev() {
+            match mount {
+                Mount::Directory(directory) => {
+                    let path = directory.join(&normalized_path);
+                    if path.is_file() {
+                        return Ok(path);
+                    }
+                }
+                Mount::Archive(archive) => {
+                    if let Some(bytes) = archive.read(&normalized_path) {
+                        let path = std::env::temp_dir().join("piston_shooty").join(&normalized_path);
+                        if let Some(parent) = path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::write(&path, bytes?)?;
+                        return Ok(path);
+                    }
+                }
+            }
+        }
+
+        Err(io::Error::new(io::ErrorKind::NotFound, format!("no mounted asset at {}", normalized_path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh file under the temp directory, named for the test so tests running at once don't share one.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("piston_shooty-{}-{}", std::process::id(), name))
+    }
+
+    fn write_archive(name: &str, index_length: u64, rest: &[u8]) -> PathBuf {
+        let path = temp_path(name);
+        let mut bytes = ARCHIVE_MAGIC.to_vec();
+        bytes.extend_from_slice(&index_length.to_le_bytes());
+        bytes.extend_from_slice(rest);
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn open_error_kind(path: &Path) -> io::ErrorKind {
+        let result = PackedArchive::open(path);
+        fs::remove_file(path).unwrap();
+        match result {
+            Ok(_) => panic!("opened a corrupt archive"),
+            Err(err) => err.kind(),
+        }
+    }
+
+    #[test]
+    fn open_rejects_an_index_longer_than_the_file() {
+        let path = write_archive("huge-index.pak", u64::max_value(), b"{}");
+        assert_eq!(open_error_kind(&path), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn open_rejects_a_truncated_index() {
+        let path = write_archive("truncated-index.pak", 3, b"{}");
+        assert_eq!(open_error_kind(&path), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_rejects_an_entry_past_the_end() {
+        let index = br#"{"a.txt":[0,1000000000000]}"#;
+        let mut rest = index.to_vec();
+        rest.extend_from_slice(b"abc");
+        let path = write_archive("entry-past-end.pak", index.len() as u64, &rest);
+
+        let archive = PackedArchive::open(&path).unwrap();
+        let result = archive.read("a.txt").unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn packs_and_reads_back() {
+        let directory = temp_path("pack-source");
+        fs::create_dir_all(directory.join("textures")).unwrap();
+        fs::write(directory.join("textures").join("a.png"), b"not really a png").unwrap();
+        let path = temp_path("packed.pak");
+
+        assert_eq!(write_packed_archive(&directory, &path).unwrap(), 1);
+        let archive = PackedArchive::open(&path).unwrap();
+        let bytes = archive.read("textures/a.png").unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(bytes, b"not really a png".to_vec());
+    }
+}
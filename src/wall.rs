@@ -1,9 +1,11 @@
 use crate::collidable::Collidable;
+use crate::collidable::Collision;
 use crate::collidable_object::CollidableObject;
 use crate::game_object::GameObject;
 use crate::object_type::ObjectType;
 use crate::renderable::Renderable;
 use crate::renderable_object::RenderableObject;
+use crate::simulation::WorldReq;
 use crate::vector2::Vector2;
 
 pub struct Wall {
@@ -13,6 +15,11 @@ pub struct Wall {
     pub renderable_object: RenderableObject,
     pub collidable_object: CollidableObject,
     pub is_visible: bool,
+    // Plain, breakable, glass or bumper. What each does is up to the collision table.
+    pub object_type: ObjectType,
+    // Hits it takes, if the table says anything destroys it.
+    pub health: u32,
+    pub should_delete: bool,
 }
 
 impl GameObject for Wall {
@@ -29,16 +36,15 @@ impl GameObject for Wall {
     }
 
     fn get_should_delete(&self) -> bool {
-        false
+        self.should_delete
     }
 
-    #[allow(unused_variables)]
     fn set_should_delete(&mut self, should_delete: bool) {
-        // do nothing
+        self.should_delete = should_delete
     }
 
     fn get_object_type(&self) -> ObjectType {
-        ObjectType::Wall
+        self.object_type
     }
 }
 
@@ -57,5 +63,25 @@ impl Collidable for Wall {
         &self.collidable_object
     }
 
-    fn collide(&mut self, _other_object_type: ObjectType) {}
+    // Walls never move.
+    fn get_velocity(&self) -> Vector2 {
+        Vector2::default()
+    }
+
+    fn set_velocity(&mut self, _velocity: Vector2) {}
+
+    fn set_position(&mut self, _position: Vector2) {}
+
+    fn collide(&mut self, _collision: &Collision) -> Vec<WorldReq> {
+        Vec::new()
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    fn survives_hit(&mut self, _direction_to_hitter: Vector2, _hitter_kind_names: &[String]) -> bool {
+        self.health = self.health.saturating_sub(1);
+        self.health > 0
+    }
 }
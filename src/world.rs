@@ -1,6 +1,7 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ops::Deref;
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::mpsc::Receiver;
 
@@ -10,15 +11,9 @@ use conrod_core::position::Positionable;
 use conrod_core::position::Sizeable;
 use conrod_core::widget::Widget;
 use conrod_core::UiCell;
-use gfx_device_gl::Resources;
-use nalgebra;
-use ncollide2d;
-use ncollide2d::bounding_volume;
-use ncollide2d::bounding_volume::BoundingVolume;
-use ncollide2d::shape::Cuboid;
 use piston_window::*;
 
-use crate::collidable::Collidable;
+use crate::fixed_timestep::FixedTimestep;
 use crate::fps_counter::FpsCounter;
 use crate::game_state::GameState;
 use crate::game_state::GameStateType;
@@ -27,172 +22,103 @@ use crate::game_state::UPDATE_RESULT_FAIL;
 use crate::game_state::UPDATE_RESULT_RUNNING;
 use crate::game_state::UPDATE_RESULT_SUCCESS;
 use crate::game_state_utils;
-use crate::gun::BULLET_SCALE;
 use crate::input;
 use crate::gun_concept::GunConcept;
-use crate::object_type::ObjectType;
 use crate::player::Player;
 use crate::render_utils;
+use crate::replay;
+use crate::replay::Replay;
+use crate::replay::ReplayPlayback;
+use crate::replay::ReplayTick;
 use crate::renderable::Renderable;
+use crate::simulation::Simulation;
+use crate::sound_manager::SoundManager;
+use crate::texture_handle::TextureHandle;
+use crate::texture_manager::TextureManager;
 use crate::ui_bundle::UiBundle;
 use crate::ui_widget_ids;
-use crate::updatable::Updatable;
 use crate::vector2::*;
 
-const ENEMY_LAYER: usize = 1;
-const PROJECTILE_LAYER: usize = 2;
-
-pub struct GameEndedState {
-    pub game_ended: bool,
-    pub won: bool,
-}
-
-// TODO: Split DynamicRenderable into Updatable and Collidable
-pub enum WorldRequestType {
-    AddUpdatable,
-    AddDynamicRenderable,
-}
-
-pub struct WorldReq {
-    pub renderable: Option<Rc<RefCell<Renderable>>>,
-    pub updatable: Option<Rc<RefCell<Updatable>>>,
-    pub collidable: Option<Rc<RefCell<Collidable>>>,
-    pub req_type: WorldRequestType,
-}
-
-// TODO: Add self/guns/bullets to here.
+// The piston/conrod presentation of a level. All of the gameplay lives in `simulation`.
 pub struct World {
-    pub renderables: Vec<Vec<Rc<RefCell<Renderable>>>>,
-    // doesn't need to be a refcell but how do we make it not???????
-    pub collidables: Vec<Rc<RefCell<Collidable>>>,
-    pub updatables: Vec<Rc<RefCell<Updatable>>>,
-    pub game_ended_state: GameEndedState,
-    pub player: Rc<RefCell<Player>>,
+    pub simulation: Simulation,
+    pub fixed_timestep: FixedTimestep,
+    // The input the next tick will see, built up from however many frames ran since the last tick.
+    pub tick_key_states: HashMap<Key, input::ButtonState>,
+    pub tick_mouse_states: HashMap<MouseButton, input::ButtonState>,
+    pub tick_mouse_pos: Vector2,
+    // Every tick's input gets appended here while recording, and written out when the level ends.
+    pub replay_recording: Option<Replay>,
+    // When set, ticks take their input from the replay instead of the player.
+    pub replay_playback: Option<ReplayPlayback>,
     pub receiver: Receiver<u64>,
     pub should_display_level_name: bool,
     pub name: String,
+    // Gun shots the level was designed to be beaten in, shown when it's won.
+    pub par: Option<usize>,
     pub fps_counter: FpsCounter,
     pub image_map: conrod_core::image::Map<G2dTexture>,
+    pub image_ids_by_texture_name: HashMap<String, conrod_core::image::Id>,
+    pub texture_manager: Rc<RefCell<TextureManager>>,
+    pub sound_manager: Rc<RefCell<SoundManager>>,
 }
 
 impl World {
-    pub fn add_renderable_at_layer(&mut self, renderable: Rc<RefCell<Renderable>>, layer: usize) {
-        while self.renderables.len() <= layer {
-            self.renderables.push(Vec::new());
-        }
-        self.renderables[layer].push(renderable);
-    }
-
-    pub fn add_collidable(&mut self, collidable: Rc<RefCell<Collidable>>) {
-        self.collidables.push(collidable);
-    }
-
-    pub fn add_updatable(&mut self, updatable: Rc<RefCell<Updatable>>) {
-        self.updatables.push(updatable);
-    }
-
-    fn is_victorious(&self) -> bool {
-        for renderable in &self.renderables[ENEMY_LAYER] {
-            if renderable.borrow().get_object_type() == ObjectType::Enemy {
-                return false;
-            }
-        }
-
-        true
-    }
-
-    fn can_take_action(&self) -> bool {
-        let mut can_take_action = self.player.borrow().can_shoot_gun();
-        can_take_action = can_take_action || self.player.borrow().can_shoot_bullet();
-        can_take_action
-    }
-
-    fn was_defeated(&self) -> bool {
-        if self.can_take_action() {
-            return false;
-        }
-
-        for renderable_layer in &self.renderables {
-            for renderable in renderable_layer {
-                if renderable.borrow().get_object_type() == ObjectType::Bullet || renderable.borrow().get_object_type() == ObjectType::GunAxe {
-                    return false;
-                }
-            }
-        }
-
-        true
-    }
-
     fn update_game_running(&mut self, key_states: &HashMap<Key, input::ButtonState>, mouse_states: &HashMap<MouseButton, input::ButtonState>, mouse_pos: &Vector2, args: UpdateArgs) -> UpdateResult {
         let _ = self.receiver.try_recv().map(|_| self.should_display_level_name = false);
 
-        if self.is_victorious() {
-            self.game_ended_state = GameEndedState {
-                game_ended: true,
-                won: true,
-            };
-            return UPDATE_RESULT_RUNNING;
-        }
-
-        if self.was_defeated() {
-            self.game_ended_state = GameEndedState {
-                game_ended: true,
-                won: false,
-            };
-            return UPDATE_RESULT_RUNNING;
+        if self.replay_playback.is_none() {
+            input::merge_input(&mut self.tick_key_states, key_states);
+            input::merge_input(&mut self.tick_mouse_states, mouse_states);
+            self.tick_mouse_pos = *mouse_pos;
         }
 
-        for collidable1 in &self.collidables {
-            for collidable2 in &self.collidables {
-                if Rc::ptr_eq(collidable1, collidable2) {
-                    continue;
-                }
+        let num_ticks = self.fixed_timestep.advance(args.dt);
+        let tick_args = UpdateArgs {
+            dt: self.fixed_timestep.tick_duration,
+        };
+        for _ in 0..num_ticks {
+            if let Some(replay_playback) = &mut self.replay_playback {
+                let tick = replay_playback.next_tick();
+                self.tick_key_states = tick.key_states;
+                self.tick_mouse_states = tick.mouse_states;
+                self.tick_mouse_pos = tick.mouse_pos;
+            }
 
-                if collides(collidable1.borrow().deref(), collidable2.borrow().deref()) {
-                    collidable1.borrow_mut().collide(collidable2.borrow().get_object_type());
-                    collidable2.borrow_mut().collide(collidable1.borrow().get_object_type());
-                }
+            if let Some(replay_recording) = &mut self.replay_recording {
+                replay_recording.ticks.push(ReplayTick {
+                    key_states: self.tick_key_states.clone(),
+                    mouse_states: self.tick_mouse_states.clone(),
+                    mouse_pos: self.tick_mouse_pos,
+                });
             }
-        }
 
-        for renderable_layer in &mut self.renderables {
-            renderable_layer.retain(|ref renderable| !renderable.borrow().get_should_delete());
+            self.simulation.update(&self.tick_key_states, &self.tick_mouse_states, &self.tick_mouse_pos, tick_args);
+            input::update_input(&mut self.tick_key_states, &mut self.tick_mouse_states);
         }
 
-        self.updatables.retain(|ref updatable| !updatable.borrow().get_should_delete());
-
-        self.collidables.retain(|ref collidable| !collidable.borrow().get_should_delete());
-
-        let mut world_reqs: Vec<WorldReq> = Vec::new();
-        for updatable in &self.updatables {
-            let current_world_reqs = &mut updatable.borrow_mut().update(&key_states, &mouse_states, &mouse_pos, args);
-            world_reqs.append(current_world_reqs);
+        if self.simulation.game_ended_state.game_ended {
+            self.save_replay();
         }
 
-        for world_req in world_reqs {
-            match world_req.req_type {
-                WorldRequestType::AddDynamicRenderable => {
-                    assert!(world_req.renderable.is_some());
-                    assert!(world_req.collidable.is_some());
-                    if let Some(renderable) = world_req.renderable {
-                        self.add_renderable_at_layer(renderable, PROJECTILE_LAYER);
-                    }
-                    if let Some(collidable) = world_req.collidable {
-                        self.add_collidable(collidable);
-                    }
-                }
-                WorldRequestType::AddUpdatable => {
-                    assert!(world_req.updatable.is_some());
-                    if let Some(updatable) = world_req.updatable {
-                        self.add_updatable(updatable);
-                    }
-                }
-            }
+        for sound in self.simulation.sounds_to_play.drain(..) {
+            self.sound_manager.borrow_mut().play(&sound);
         }
 
         UPDATE_RESULT_RUNNING
     }
 
+    fn save_replay(&mut self) {
+        if let Some(replay) = self.replay_recording.take() {
+            let replay_directory = Path::new(&replay.config.replay_config.replay_directory).to_path_buf();
+            // Losing the replay is no reason to lose the game too.
+            match replay::write_replay_to_file(&replay, &replay_directory) {
+                Ok(path) => println!("Saved replay to {}", path.display()),
+                Err(err) => println!("Couldn't save the replay: {}", err),
+            }
+        }
+    }
+
     fn update_game_ended_lost(&self, mouse_states: &HashMap<MouseButton, input::ButtonState>) -> UpdateResult {
         if game_state_utils::did_click(&mouse_states) {
             UPDATE_RESULT_FAIL
@@ -212,18 +138,27 @@ impl World {
     // todo: gif of ctrl+f of shots_taken in our codebase
     fn update_ui(&self, ui_bundle: &mut UiBundle) {
         // TODO: Please help.
-        let gun_concepts = &self.player.borrow().gun_concepts;
+        let gun_concepts = &self.simulation.player.borrow().gun_concepts;
         ui_bundle.ids.guns_hud.resize(gun_concepts.len(), &mut ui_bundle.conrod_ui.widget_id_generator());
         ui_bundle.ids.shots_taken_hud.resize(gun_concepts.len(), &mut ui_bundle.conrod_ui.widget_id_generator());
         ui_bundle.ids.bullets_remaining_hud.resize(gun_concepts.len(), &mut ui_bundle.conrod_ui.widget_id_generator());
         ui_bundle.ids.bullets_hud.resize(gun_concepts.len(), &mut ui_bundle.conrod_ui.widget_id_generator());
+        ui_bundle.ids.bullet_kinds_hud.resize(gun_concepts.len(), &mut ui_bundle.conrod_ui.widget_id_generator());
+        ui_bundle.ids.enemy_health_hud.resize(self.simulation.enemies.len(), &mut ui_bundle.conrod_ui.widget_id_generator());
 
         let mut ui_cell = ui_bundle.conrod_ui.set_widgets();
         conrod_core::widget::Canvas::new().pad(40.0).color(conrod_core::color::TRANSPARENT).set(ui_bundle.ids.canvas, &mut ui_cell);
 
-        if self.game_ended_state.game_ended {
-            if self.game_ended_state.won {
-                render_utils::draw_text_overlay("Success! Click to continue.", &mut ui_cell, &ui_bundle.ids, conrod_core::color::WHITE, 36);
+        if self.simulation.game_ended_state.game_ended {
+            if self.simulation.game_ended_state.won {
+                let success_text = match self.par {
+                    Some(par) => {
+                        let shots_taken: usize = gun_concepts.iter().map(|gun_concept| gun_concept.borrow().shots_taken()).sum();
+                        format!("Success in {} shots (par {})! Click to continue.", shots_taken, par)
+                    }
+                    None => "Success! Click to continue.".to_string(),
+                };
+                render_utils::draw_text_overlay(success_text.as_str(), &mut ui_cell, &ui_bundle.ids, conrod_core::color::WHITE, 36);
             } else {
                 render_utils::draw_text_overlay("Defeat! Click to retry.", &mut ui_cell, &ui_bundle.ids, conrod_core::color::WHITE, 36);
             }
@@ -234,7 +169,7 @@ impl World {
         let mut id_gun_right = ui_bundle.ids.canvas;
         let mut width_gun_right = 0.0;
         for i in 0..gun_concepts.len() {
-            let player = self.player.borrow();
+            let player = self.simulation.player.borrow();
             let current_gun_concept = player.gun_concepts[i].clone();
             let ids = &ui_bundle.ids;
 
@@ -247,17 +182,44 @@ impl World {
             width_gun_right = f64::from(gun_texture.get_width());
         }
 
+        self.draw_enemies_hud(&mut ui_cell, &ui_bundle.ids);
+
         self.fps_counter.update_ui(&mut ui_cell, &ui_bundle.ids);
     }
 
+    // How many enemies are left, and the health of any that take more than one hit or are still armored, over their heads.
+    fn draw_enemies_hud(&self, mut ui_cell: &mut UiCell, ids: &ui_widget_ids::Ids) {
+        let enemies_remaining_text = format!("Enemies: {}", self.simulation.enemies.len());
+        conrod_core::widget::Text::new(enemies_remaining_text.as_str()).font_size(18).color(conrod_core::color::WHITE).mid_top_of(ids.canvas).set(ids.enemies_remaining_hud, &mut ui_cell);
+
+        // The HUD's origin is the middle of the window, with y going up.
+        let half_window_width = f64::from(self.simulation.tuning.window_width) / 2.0;
+        let half_window_height = f64::from(self.simulation.tuning.window_height) / 2.0;
+        for (i, enemy) in self.simulation.enemies.iter().enumerate() {
+            let enemy = enemy.borrow();
+            if enemy.max_health <= 1 && !enemy.is_armored {
+                continue;
+            }
+
+            let health_text = if enemy.is_armored {
+                format!("{}/{} armored", enemy.health, enemy.max_health)
+            } else {
+                format!("{}/{}", enemy.health, enemy.max_health)
+            };
+            let above_enemy = enemy.collidable_object.height * enemy.scale / 2.0 + 8.0;
+            conrod_core::widget::Text::new(health_text.as_str())
+                .font_size(12)
+                .color(conrod_core::color::WHITE)
+                .x_y(enemy.position.x - half_window_width, half_window_height - enemy.position.y + above_enemy)
+                .set(ids.enemy_health_hud[i], &mut ui_cell);
+        }
+    }
+
     fn draw_bullets_remaining_text(&self, mut ui_cell: &mut UiCell, i: usize, current_gun_concept: Rc<RefCell<GunConcept>>, ids: &ui_widget_ids::Ids) {
         // draw bullets remaining
-        let bullets_remaining = if current_gun_concept.borrow().has_shot_bullet() {
-            0
-        } else {
-            1
-        };
-        let bullets_remaining_text = format!("{}/{}", bullets_remaining, 1);
+        let bullets_shot = current_gun_concept.borrow().bullets_shot();
+        let bullet_count = current_gun_concept.borrow().bullet_count();
+        let bullets_remaining_text = format!("{}/{}", bullet_count - bullets_shot, bullet_count);
         conrod_core::widget::Text::new(bullets_remaining_text.as_str())
             .font_size(18)
             .color(conrod_core::color::WHITE)
@@ -278,13 +240,22 @@ impl World {
     }
 
     fn draw_bullet_image_hud(&self, ids: &ui_widget_ids::Ids, mut ui_cell: &mut UiCell, i: usize, current_gun_concept: Rc<RefCell<GunConcept>>) {
-        let bullet_image_id = current_gun_concept.borrow().bullet_image_id();
         let bullet_texture = current_gun_concept.borrow().bullet_texture().clone();
+        let bullet_image_id = self.image_ids_by_texture_name[&bullet_texture.name];
+        let bullet_scale = self.simulation.tuning.bullet_scale * current_gun_concept.borrow().bullet_behavior().scale_multiplier();
         conrod_core::widget::Image::new(bullet_image_id)
-            .w_h((BULLET_SCALE * 1.5) * f64::from(bullet_texture.get_width()), (BULLET_SCALE * 1.5) * f64::from(bullet_texture.get_height()))
+            .w_h((bullet_scale * 1.5) * f64::from(bullet_texture.get_width()), (bullet_scale * 1.5) * f64::from(bullet_texture.get_height()))
             .down_from(ids.guns_hud[i], 30.0)
             .align_middle_x_of(ids.guns_hud[i])
             .set(ids.bullets_hud[i], &mut ui_cell);
+
+        // draw bullet kind
+        conrod_core::widget::Text::new(current_gun_concept.borrow().bullet_behavior().name())
+            .font_size(12)
+            .color(conrod_core::color::WHITE)
+            .down_from(ids.bullets_hud[i], 6.0)
+            .align_middle_x_of(ids.bullets_hud[i])
+            .set(ids.bullet_kinds_hud[i], &mut ui_cell);
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -297,21 +268,15 @@ impl World {
         i: usize,
         player: &Player,
         current_gun_concept: Rc<RefCell<GunConcept>>,
-    ) -> Rc<Texture<Resources>> {
+    ) -> TextureHandle {
         // Draw gun texture & highlight selected
         let is_selected_gun = i == player.current_gun_concept_index;
-        let gun_image_id = if is_selected_gun {
-            current_gun_concept.borrow().selected_gun_image_id()
-        } else {
-            current_gun_concept.borrow().gun_image_id()
-        };
-
-        // note: gun_texture is only used for the width and height right now
         let gun_texture = if is_selected_gun {
             current_gun_concept.borrow().selected_gun_texture().clone()
         } else {
             current_gun_concept.borrow().gun_texture().clone()
         };
+        let gun_image_id = self.image_ids_by_texture_name[&gun_texture.name];
 
         let mut gun_image = conrod_core::widget::Image::new(gun_image_id).w_h(f64::from(gun_texture.get_width()), f64::from(gun_texture.get_height()));
         if id_gun_right == ids.canvas {
@@ -328,9 +293,11 @@ impl GameState for World {
     fn render(&mut self, c: Context, mut gl: &mut G2d, ui_bundle: &mut UiBundle) {
         self.fps_counter.calculate_fps();
 
-        for i in 0..self.renderables.len() {
-            for renderable in &self.renderables[i] {
-                render_renderable(&c, &mut gl, renderable.borrow().deref());
+        let alpha = self.fixed_timestep.alpha();
+        let mut texture_manager = self.texture_manager.borrow_mut();
+        for renderable_layer in &self.simulation.renderables {
+            for renderable in renderable_layer {
+                render_renderable(&c, &mut gl, &mut texture_manager, renderable.borrow().deref(), alpha);
             }
         }
 
@@ -347,15 +314,15 @@ impl GameState for World {
     ) -> UpdateResult {
         self.update_ui(ui_bundle);
 
-        if !self.game_ended_state.game_ended && !self.game_ended_state.won {
+        if !self.simulation.game_ended_state.game_ended && !self.simulation.game_ended_state.won {
             return self.update_game_running(&key_states, &mouse_states, &mouse_pos, args);
         }
 
-        if self.game_ended_state.game_ended && !self.game_ended_state.won {
+        if self.simulation.game_ended_state.game_ended && !self.simulation.game_ended_state.won {
             return self.update_game_ended_lost(&mouse_states);
         }
 
-        if self.game_ended_state.game_ended && self.game_ended_state.won {
+        if self.simulation.game_ended_state.game_ended && self.simulation.game_ended_state.won {
             return self.update_game_ended_won(&mouse_states);
         }
 
@@ -368,30 +335,26 @@ impl GameState for World {
     }
 }
 
-fn collides(collidable1: &Collidable, collidable2: &Collidable) -> bool {
-    let collidable1_aabb_cuboid2 = create_aabb_cuboid2(collidable1);
-    let collidable2_aabb_cuboid2 = create_aabb_cuboid2(collidable2);
-    collidable1_aabb_cuboid2.intersects(&collidable2_aabb_cuboid2)
-}
-
-fn create_aabb_cuboid2(collidable: &Collidable) -> ncollide2d::bounding_volume::aabb::AABB<f64> {
-    let half_extents: nalgebra::core::Vector2<f64> =
-        nalgebra::core::Vector2::new(collidable.get_collidable_object().width as f64 * 0.5 * collidable.get_scale(), collidable.get_collidable_object().height as f64 * 0.5 * collidable.get_scale());
-    let cuboid2 = Cuboid::new(half_extents);
-    let cuboid2_pos = nalgebra::geometry::Isometry2::new(nalgebra::core::Vector2::new(collidable.get_position().x, collidable.get_position().y), collidable.get_rotation());
-    bounding_volume::aabb(&cuboid2, &cuboid2_pos)
-}
-
-fn render_renderable(c: &Context, gl: &mut G2d, renderable: &Renderable) {
+// `alpha` is how far we are between the last two ticks; we draw the object that far along its way.
+fn render_renderable(c: &Context, gl: &mut G2d, texture_manager: &mut TextureManager, renderable: &Renderable, alpha: f64) {
     if !renderable.is_visible() {
         return;
     }
 
-    let texture = &renderable.get_renderable_object().texture;
+    let previous_position = *renderable.get_previous_position();
+    let position = previous_position + (*renderable.get_position() - previous_position) * alpha;
+    let previous_rotation = renderable.get_previous_rotation();
+    let rotation = previous_rotation + (renderable.get_rotation() - previous_rotation) * alpha;
+
+    // Building the level loaded every texture it uses, so there's nothing left to fail here.
+    let texture = match texture_manager.get(&renderable.get_renderable_object().texture.name) {
+        Ok(texture) => texture,
+        Err(_) => return,
+    };
     let transform = c
         .transform
-        .trans(renderable.get_position().x, renderable.get_position().y)
-        .rot_rad(renderable.get_rotation())
+        .trans(position.x, position.y)
+        .rot_rad(rotation)
         .trans((f64::from(texture.get_size().0)) * -0.5 * renderable.get_scale(), (f64::from(texture.get_size().1)) * -0.5 * renderable.get_scale())
         .scale(renderable.get_scale(), renderable.get_scale());
     image(texture.deref(), transform, gl);